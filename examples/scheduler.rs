@@ -3,7 +3,6 @@ extern crate cron;
 use std::{sync::Arc, time};
 
 use crate::cron::scheduler::{CronTrigger, EveryTrigger, Scheduler};
-use chrono::Utc;
 use tokio::{self};
 
 #[tokio::main]
@@ -28,7 +27,7 @@ async fn main() {
         });
 
         loop {
-            let now = Utc::now().timestamp_millis();
+            let now = scheduler.now();
             let next_firetime = scheduler.get_next_firetime().unwrap();
 
             if now >= next_firetime {
@@ -0,0 +1,406 @@
+//! Command line tool for checking cron expressions.
+//!
+//! ```text
+//! cron validate <expr>
+//! cron next <expr> [-n <count>] [--from <time>] [--tz <offset>]
+//! cron contains <expr> <time> [--tz <offset>]
+//! cron describe <expr>
+//! ```
+//!
+//! Every command takes `--json` to print JSON instead of text. `--tz` takes a fixed UTC offset
+//! rather than a named time zone, so times don't follow daylight saving changes.
+
+use chrono::{DateTime, Duration, FixedOffset, NaiveDateTime, TimeZone, Utc};
+use cron::parse::{CronExpr, CronParseError, CronParseErrorKind};
+use cron::Cron;
+use serde_json::{json, Value};
+use std::env;
+use std::process::ExitCode;
+
+const USAGE: &str = "\
+Usage:
+    cron validate <expr>
+    cron next <expr> [-n <count>] [--from <time>] [--tz <offset>]
+    cron contains <expr> <time> [--tz <offset>]
+    cron describe <expr>
+
+Options:
+    -n <count>       Number of times to list [default: 10]
+    --from <time>    Time to list from [default: now]
+    --tz <offset>    UTC offset the expression runs in, like +02:00 [default: UTC].
+                     Named time zones aren't supported, and offsets don't follow DST.
+    --json           Print JSON instead of text
+
+Times are RFC 3339 (2024-01-01T09:00:00+02:00) or 'YYYY-MM-DD HH:MM:SS' in the --tz offset.
+Expressions have 7 fields: sec min hour day-of-month month day-of-week year.
+Exits with 1 if the expression is invalid or, for 'contains', doesn't contain the time, and
+with 2 on usage errors.";
+
+struct Args {
+    command: String,
+    positional: Vec<String>,
+    count: usize,
+    from: Option<String>,
+    tz: FixedOffset,
+    json: bool,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, String> {
+    let command = args.next().ok_or("missing command")?;
+    let mut parsed = Args {
+        command,
+        positional: Vec::new(),
+        count: 10,
+        from: None,
+        tz: FixedOffset::east_opt(0).unwrap(),
+        json: false,
+    };
+
+    while let Some(arg) = args.next() {
+        let mut value = |name: &str| args.next().ok_or(format!("missing value for {}", name));
+        match arg.as_str() {
+            "-n" | "--count" => {
+                parsed.count = value(&arg)?
+                    .parse()
+                    .map_err(|_| "the count must be a number".to_string())?
+            }
+            "--from" => parsed.from = Some(value(&arg)?),
+            "--tz" => parsed.tz = parse_offset(&value(&arg)?)?,
+            "--json" => parsed.json = true,
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with('-') && arg.len() > 1 => {
+                return Err(format!("unknown option '{}'", arg))
+            }
+            _ => parsed.positional.push(arg),
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_offset(s: &str) -> Result<FixedOffset, String> {
+    if s.eq_ignore_ascii_case("utc") || s == "Z" {
+        return Ok(FixedOffset::east_opt(0).unwrap());
+    }
+    let error = || format!("invalid UTC offset '{}'", s);
+    let (sign, rest) = match s.as_bytes().first() {
+        Some(b'+') => (1, &s[1..]),
+        Some(b'-') => (-1, &s[1..]),
+        Some(c) if c.is_ascii_alphabetic() => {
+            return Err(format!(
+                "named time zones like '{}' aren't supported; use a UTC offset like +02:00",
+                s
+            ))
+        }
+        _ => return Err(error()),
+    };
+    let (hours, minutes) = rest.split_once(':').unwrap_or((rest, "0"));
+    let hours: i32 = hours.parse().map_err(|_| error())?;
+    let minutes: i32 = minutes.parse().map_err(|_| error())?;
+    if !(0..60).contains(&minutes) {
+        return Err(error());
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(error)
+}
+
+fn parse_time(s: &str, tz: FixedOffset) -> Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+        .and_then(|naive| tz.from_local_datetime(&naive).single())
+        .map(|time| time.with_timezone(&Utc))
+        .ok_or_else(|| format!("invalid time '{}'", s))
+}
+
+/// Crons run on UTC, so times are shifted by the offset to match the expression against the
+/// offset's wall clock.
+fn to_wall_clock(time: DateTime<Utc>, tz: FixedOffset) -> DateTime<Utc> {
+    time + Duration::seconds(i64::from(tz.local_minus_utc()))
+}
+
+fn from_wall_clock(time: DateTime<Utc>, tz: FixedOffset) -> DateTime<FixedOffset> {
+    (time - Duration::seconds(i64::from(tz.local_minus_utc()))).with_timezone(&tz)
+}
+
+/// Returns the first `count` times of a cron after `from`, in the offset.
+fn next_times(
+    cron: Cron,
+    from: DateTime<Utc>,
+    tz: FixedOffset,
+    count: usize,
+) -> Vec<DateTime<FixedOffset>> {
+    cron.iter_after(to_wall_clock(from, tz))
+        .take(count)
+        .map(|time| from_wall_clock(time, tz))
+        .collect()
+}
+
+fn expr_arg(args: &Args, expected: usize) -> Result<&str, String> {
+    match args.positional.len() {
+        n if n == expected => Ok(&args.positional[0]),
+        0 => Err("missing expression".to_string()),
+        _ => Err(format!(
+            "expected {} argument(s) but found {}; quote the expression",
+            expected,
+            args.positional.len()
+        )),
+    }
+}
+
+fn error_json(expr: &str, error: &CronParseError) -> Value {
+    let kind = match error.kind() {
+        CronParseErrorKind::FieldCount(_) => "field_count",
+        CronParseErrorKind::InvalidField(_) => "invalid_field",
+        CronParseErrorKind::BothDays => "both_days",
+        _ => "malformed",
+    };
+    let field = error.field().map(|field| {
+        json!({
+            "name": field.name(),
+            "index": field.index(),
+            "value": expr.split_whitespace().nth(field.index()),
+        })
+    });
+    json!({ "kind": kind, "field": field, "message": error.to_string() })
+}
+
+fn error_text(expr: &str, error: &CronParseError) -> String {
+    match error.field() {
+        Some(field) => format!(
+            "{} (field {}: '{}')",
+            error,
+            field.index() + 1,
+            expr.split_whitespace()
+                .nth(field.index())
+                .unwrap_or_default()
+        ),
+        None => error.to_string(),
+    }
+}
+
+enum Failure {
+    Usage(String),
+    Invalid(String, CronParseError),
+}
+
+impl From<String> for Failure {
+    fn from(e: String) -> Self {
+        Failure::Usage(e)
+    }
+}
+
+fn print_invalid(args: &Args, expr: &str, error: &CronParseError) {
+    if args.json {
+        println!(
+            "{}",
+            json!({ "expression": expr, "valid": false, "error": error_json(expr, error) })
+        );
+    } else {
+        println!("{}", error_text(expr, error));
+    }
+}
+
+/// Prints the output and returns whether the command succeeded.
+fn run(args: &Args) -> Result<bool, Failure> {
+    let print = |text: String, json: Value| {
+        if args.json {
+            println!("{}", json);
+        } else {
+            println!("{}", text);
+        }
+    };
+
+    let parse = |expr: &str| -> Result<CronExpr, Failure> {
+        expr.parse::<CronExpr>()
+            .map_err(|e| Failure::Invalid(expr.to_string(), e))
+    };
+
+    match args.command.as_str() {
+        "validate" => {
+            let expr = expr_arg(args, 1)?;
+            match expr.parse::<CronExpr>() {
+                Ok(_) => {
+                    print(
+                        "valid".to_string(),
+                        json!({ "expression": expr, "valid": true }),
+                    );
+                    Ok(true)
+                }
+                Err(e) => {
+                    print_invalid(args, expr, &e);
+                    Ok(false)
+                }
+            }
+        }
+        "next" => {
+            let expr = expr_arg(args, 1)?;
+            let cron = Cron::new(parse(expr)?);
+            let from = match &args.from {
+                Some(from) => parse_time(from, args.tz)?,
+                None => Utc::now(),
+            };
+            let times: Vec<String> = next_times(cron, from, args.tz, args.count)
+                .iter()
+                .map(DateTime::to_rfc3339)
+                .collect();
+            print(
+                times.join("\n"),
+                json!({ "expression": expr, "from": from.with_timezone(&args.tz).to_rfc3339(), "times": times }),
+            );
+            Ok(true)
+        }
+        "contains" => {
+            let expr = expr_arg(args, 2)?;
+            let cron = Cron::new(parse(expr)?);
+            let time = parse_time(&args.positional[1], args.tz)?;
+            let contains = cron.contains(to_wall_clock(time, args.tz));
+            print(
+                contains.to_string(),
+                json!({ "expression": expr, "time": time.with_timezone(&args.tz).to_rfc3339(), "contains": contains }),
+            );
+            Ok(contains)
+        }
+        "describe" => {
+            let expr = expr_arg(args, 1)?;
+            let description = parse(expr)?.describe().to_string();
+            print(
+                description.clone(),
+                json!({ "expression": expr, "description": description }),
+            );
+            Ok(true)
+        }
+        command => Err(Failure::Usage(format!("unknown command '{}'", command))),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) if e.is_empty() => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match run(&args) {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(Failure::Usage(e)) => {
+            eprintln!("error: {}", e);
+            ExitCode::from(2)
+        }
+        Err(Failure::Invalid(expr, e)) => {
+            print_invalid(&args, &expr, &e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Result<Args, String> {
+        parse_args(list.iter().map(|arg| arg.to_string()))
+    }
+
+    fn offset(hours: i32) -> FixedOffset {
+        FixedOffset::east_opt(hours * 3600).unwrap()
+    }
+
+    #[test]
+    fn arguments() {
+        let parsed = args(&[
+            "next",
+            "0 0 12 * * ? *",
+            "-n",
+            "3",
+            "--tz",
+            "+02:00",
+            "--json",
+        ]);
+        let parsed = parsed.unwrap();
+        assert_eq!(parsed.command, "next");
+        assert_eq!(parsed.positional, ["0 0 12 * * ? *"]);
+        assert_eq!(parsed.count, 3);
+        assert_eq!(parsed.tz, offset(2));
+        assert!(parsed.json);
+        assert_eq!(expr_arg(&parsed, 1), Ok("0 0 12 * * ? *"));
+
+        // unquoted expressions are split into several arguments
+        let parsed = args(&["describe", "0", "0", "12", "*", "*", "?", "*"]).unwrap();
+        assert!(expr_arg(&parsed, 1).unwrap_err().contains("quote"));
+
+        assert_eq!(args(&["next", "--help"]).err().unwrap(), "");
+        assert!(args(&[]).is_err());
+        assert!(args(&["next", "-n", "many"]).is_err());
+        assert!(args(&["next", "--from"]).is_err());
+        assert!(args(&["next", "--verbose"]).is_err());
+    }
+
+    #[test]
+    fn offsets() {
+        assert_eq!(parse_offset("UTC"), Ok(offset(0)));
+        assert_eq!(parse_offset("Z"), Ok(offset(0)));
+        assert_eq!(parse_offset("+02:00"), Ok(offset(2)));
+        assert_eq!(parse_offset("-5"), Ok(offset(-5)));
+        assert_eq!(
+            parse_offset("+05:30"),
+            Ok(FixedOffset::east_opt(5 * 3600 + 30 * 60).unwrap())
+        );
+        assert!(parse_offset("Europe/Paris")
+            .unwrap_err()
+            .contains("named time zones"));
+        assert!(parse_offset("+02:75").is_err());
+        assert!(parse_offset("+48:00").is_err());
+        assert!(parse_offset("2").is_err());
+    }
+
+    #[test]
+    fn times() {
+        let expected = Utc.with_ymd_and_hms(2024, 1, 1, 7, 0, 0).unwrap();
+        assert_eq!(
+            parse_time("2024-01-01T09:00:00+02:00", offset(0)),
+            Ok(expected)
+        );
+        assert_eq!(parse_time("2024-01-01 09:00:00", offset(2)), Ok(expected));
+        assert_eq!(parse_time("2024-01-01 09:00", offset(2)), Ok(expected));
+        assert!(parse_time("tomorrow", offset(0)).is_err());
+    }
+
+    #[test]
+    fn times_in_an_offset() {
+        let cron = Cron::new("0 0 9 * * ? *".parse().unwrap());
+        let from = Utc.with_ymd_and_hms(2024, 1, 1, 6, 0, 0).unwrap();
+
+        let times = next_times(cron.clone(), from, offset(2), 2);
+        let expected = ["2024-01-01T09:00:00+02:00", "2024-01-02T09:00:00+02:00"];
+        assert_eq!(
+            times.iter().map(DateTime::to_rfc3339).collect::<Vec<_>>(),
+            expected
+        );
+
+        let nine = parse_time("2024-01-01 09:00:00", offset(2)).unwrap();
+        assert!(cron.contains(to_wall_clock(nine, offset(2))));
+        assert!(!cron.contains(to_wall_clock(nine, offset(0))));
+    }
+
+    #[test]
+    fn invalid_expressions() {
+        let expr = "0 0 25 * * ? *";
+        let error = expr.parse::<CronExpr>().unwrap_err();
+        assert!(error_text(expr, &error).ends_with("(field 3: '25')"));
+
+        let json = error_json(expr, &error);
+        assert_eq!(json["kind"], "invalid_field");
+        assert_eq!(json["field"]["index"], 2);
+        assert_eq!(json["field"]["value"], "25");
+    }
+}
@@ -0,0 +1,670 @@
+//! A small cron daemon running the shell commands of a crontab.
+//!
+//! ```text
+//! crond [--timeout <secs>] [--shell <path>] [--poll <secs>] <crontab>
+//! ```
+//!
+//! The crontab uses the format of [`cron::crontab`], with 7 field expressions evaluated in UTC,
+//! not in local time like the system cron. Each command
+//! runs with `<shell> -c <command>` and the environment assigned before it. A `CROND_TIMEOUT`
+//! assignment sets the timeout in seconds of the entries after it, overriding `--timeout`.
+//!
+//! The output and exit code of every run is logged. A command running past its timeout is
+//! killed along with the processes it started, after logging the output it wrote until then.
+//! On SIGTERM or SIGINT (ctrl-c outside of Unix) no more jobs are started, and the daemon exits
+//! once the running jobs finish.
+//!
+//! The crontab is reloaded on SIGHUP on Unix, and when its modification time changes, checked
+//! every `--poll` seconds (5 by default, 0 to disable). Jobs are identified by their command, so
+//! jobs whose command and schedule didn't change keep their state. `@reboot` jobs only run
+//! at startup.
+//!
+//! Runs missed by more than a minute, like while the machine or container was suspended, are
+//! logged and skipped, and their jobs continue with the first run after now.
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use cron::crontab::{Crontab, CrontabEntry, Schedule};
+use cron::scheduler::{CronTrigger, ResumePolicy, Retired, Scheduler, Trigger};
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::fs;
+use std::io;
+use std::process::{ExitCode, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::{Child, Command};
+#[cfg(unix)]
+use tokio::signal::unix::{signal, Signal, SignalKind};
+use tokio::task::JoinHandle;
+use tokio::task::JoinSet;
+
+const USAGE: &str = "Usage: crond [--timeout <secs>] [--shell <path>] [--poll <secs>] <crontab>";
+
+/// The environment variable setting the timeout of the following crontab entries.
+const TIMEOUT_VAR: &str = "CROND_TIMEOUT";
+
+/// How long to wait for a command's output once it ended, in case a process it started in
+/// the background still holds its output open.
+const OUTPUT_GRACE: Duration = Duration::from_secs(5);
+
+/// How late a run can be before it's skipped as missed.
+const MISSED_AFTER: Duration = Duration::from_secs(60);
+
+struct Options {
+    crontab: String,
+    timeout: Option<Duration>,
+    shell: String,
+    poll: Option<Duration>,
+}
+
+#[derive(Debug, Clone)]
+struct Job {
+    id: String,
+    command: String,
+    env: Vec<(String, String)>,
+    timeout: Option<Duration>,
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Options, String> {
+    let mut crontab = None;
+    let mut timeout = None;
+    let mut shell = "/bin/sh".to_string();
+    let mut poll = Some(Duration::from_secs(5));
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--timeout" => {
+                let value = args.next().ok_or("missing value for --timeout")?;
+                timeout = Some(parse_timeout(&value)?);
+            }
+            "--shell" => shell = args.next().ok_or("missing value for --shell")?,
+            "--poll" => {
+                let value = args.next().ok_or("missing value for --poll")?;
+                poll = Some(parse_timeout(&value)?).filter(|poll| !poll.is_zero());
+            }
+            "-h" | "--help" => return Err(String::new()),
+            _ if arg.starts_with('-') => return Err(format!("unknown option '{}'", arg)),
+            _ if crontab.is_none() => crontab = Some(arg),
+            _ => return Err(format!("unexpected argument '{}'", arg)),
+        }
+    }
+
+    Ok(Options {
+        crontab: crontab.ok_or("missing crontab path")?,
+        timeout,
+        shell,
+        poll,
+    })
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    s.parse()
+        .map(Duration::from_secs)
+        .map_err(|_| format!("invalid timeout '{}'", s))
+}
+
+fn log(message: impl AsRef<str>) {
+    println!(
+        "{} {}",
+        Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
+        message.as_ref()
+    );
+}
+
+fn job(id: String, entry: &CrontabEntry, default_timeout: Option<Duration>) -> Result<Job, String> {
+    let mut env = Vec::new();
+    let mut timeout = default_timeout;
+    for (name, value) in entry.env() {
+        if name == TIMEOUT_VAR {
+            timeout =
+                Some(parse_timeout(value).map_err(|e| format!("line {}: {}", entry.line(), e))?);
+        } else {
+            env.push((name.clone(), value.clone()));
+        }
+    }
+
+    Ok(Job {
+        id,
+        command: entry.command().to_string(),
+        env,
+        timeout,
+    })
+}
+
+struct Loaded {
+    /// The `@reboot` jobs, run at startup
+    reboot: Vec<Job>,
+    scheduled: Vec<(Job, CronTrigger)>,
+}
+
+fn load(options: &Options) -> Result<Loaded, String> {
+    let text = fs::read_to_string(&options.crontab)
+        .map_err(|e| format!("failed to read {}: {}", options.crontab, e))?;
+    let crontab: Crontab = text
+        .parse()
+        .map_err(|e| format!("{}: {}", options.crontab, e))?;
+
+    let mut reboot = Vec::new();
+    let mut scheduled = Vec::new();
+    let mut ids = HashSet::new();
+    for entry in crontab.entries() {
+        // identify jobs by their command so they survive lines moving around. repeated
+        // commands are numbered in the order they appear.
+        let mut id = entry.command().to_string();
+        for n in 2.. {
+            if ids.insert(id.clone()) {
+                break;
+            }
+            id = format!("{} #{}", entry.command(), n);
+        }
+
+        let job = job(id, entry, options.timeout)?;
+        match entry.schedule() {
+            Schedule::Reboot => reboot.push(job),
+            Schedule::Cron(expr) => {
+                let cron = cron::Cron::new((**expr).clone());
+                let trigger = CronTrigger::from_cron(job.id.clone(), entry.schedule_source(), cron);
+                scheduled.push((job, trigger));
+            }
+        }
+    }
+
+    Ok(Loaded { reboot, scheduled })
+}
+
+/// How a job's command ended.
+#[derive(Debug, PartialEq, Eq)]
+enum Status {
+    Exited(i32),
+    /// Killed by a signal it wasn't sent by the daemon
+    Killed,
+    /// Killed after running past its timeout
+    TimedOut(Duration),
+    WaitFailed(String),
+}
+
+/// The output and status of a job's command.
+#[derive(Debug)]
+struct Outcome {
+    /// The lines written to stdout and stderr, by stream
+    output: Vec<(&'static str, String)>,
+    status: Status,
+}
+
+/// Reads everything from a child's pipe in the background into a buffer, so the output
+/// written so far is kept when the reader is stopped early.
+fn read_all<R>(pipe: Option<R>) -> (Arc<Mutex<Vec<u8>>>, JoinHandle<()>)
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    let bytes = Arc::new(Mutex::new(Vec::new()));
+    let buffer = bytes.clone();
+    let reader = tokio::spawn(async move {
+        let mut pipe = match pipe {
+            Some(pipe) => pipe,
+            None => return,
+        };
+        let mut chunk = [0; 4096];
+        while let Ok(read @ 1..) = pipe.read(&mut chunk).await {
+            buffer.lock().unwrap().extend_from_slice(&chunk[..read]);
+        }
+    });
+    (bytes, reader)
+}
+
+/// Kills a command along with the processes it started, which share its process group.
+#[cfg(unix)]
+async fn kill(child: &mut Child) {
+    if let Some(pid) = child.id() {
+        // SAFETY: killpg only sends a signal, and the group is the child's own since it was
+        // started with process_group(0)
+        unsafe {
+            libc::killpg(pid as libc::pid_t, libc::SIGKILL);
+        }
+    }
+    let _ = child.kill().await;
+}
+
+#[cfg(not(unix))]
+async fn kill(child: &mut Child) {
+    let _ = child.kill().await;
+}
+
+/// Runs a job's command to completion or until its timeout.
+async fn execute(job: &Job, shell: &str) -> Result<Outcome, io::Error> {
+    let mut command = Command::new(shell);
+    command
+        .arg("-c")
+        .arg(&job.command)
+        .envs(job.env.iter().map(|(name, value)| (name, value)))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true);
+    // keep signals sent to the daemon's group, like a terminal's ctrl-c, from reaching the
+    // jobs so they can finish during a graceful shutdown. the job's own group also lets a
+    // timeout kill the processes it started.
+    #[cfg(unix)]
+    command.process_group(0);
+    let mut child = command.spawn()?;
+    let stdout = read_all(child.stdout.take());
+    let stderr = read_all(child.stderr.take());
+
+    let waited = match job.timeout {
+        Some(timeout) => tokio::time::timeout(timeout, child.wait()).await.ok(),
+        None => Some(child.wait().await),
+    };
+    let status = match waited {
+        Some(Ok(status)) => match status.code() {
+            Some(code) => Status::Exited(code),
+            None => Status::Killed,
+        },
+        Some(Err(e)) => Status::WaitFailed(e.to_string()),
+        None => {
+            kill(&mut child).await;
+            Status::TimedOut(job.timeout.unwrap_or_default())
+        }
+    };
+
+    // both streams share one grace period, after which whatever was read is kept
+    let deadline = tokio::time::Instant::now() + OUTPUT_GRACE;
+    let mut output = Vec::new();
+    for (stream, (bytes, mut reader)) in [("stdout", stdout), ("stderr", stderr)] {
+        if tokio::time::timeout_at(deadline, &mut reader)
+            .await
+            .is_err()
+        {
+            reader.abort();
+        }
+        let bytes = core::mem::take(&mut *bytes.lock().unwrap());
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            output.push((stream, line.to_string()));
+        }
+    }
+    Ok(Outcome { output, status })
+}
+
+/// Runs a job's command to completion or until its timeout, logging its output and exit code.
+async fn run(job: Job, shell: String) {
+    log(format!("{}: running `{}`", job.id, job.command));
+    let started = Instant::now();
+
+    let outcome = match execute(&job, &shell).await {
+        Ok(outcome) => outcome,
+        Err(e) => return log(format!("{}: failed to start: {}", job.id, e)),
+    };
+    for (stream, line) in &outcome.output {
+        log(format!("{} {}: {}", job.id, stream, line));
+    }
+    let status = match outcome.status {
+        Status::Exited(code) => format!("exited with code {}", code),
+        Status::Killed => "was killed by a signal".to_string(),
+        Status::TimedOut(timeout) => {
+            format!("was killed after timing out in {}s", timeout.as_secs())
+        }
+        Status::WaitFailed(e) => return log(format!("{}: failed to wait: {}", job.id, e)),
+    };
+    log(format!(
+        "{}: {} after {}ms",
+        job.id,
+        status,
+        started.elapsed().as_millis()
+    ));
+}
+
+fn modified(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|meta| meta.modified()).ok()
+}
+
+/// Replaces the scheduled jobs with the ones of a newly loaded crontab.
+fn reload(
+    scheduler: &mut Scheduler,
+    jobs: &mut HashMap<String, Job>,
+    scheduled: Vec<(Job, CronTrigger)>,
+) {
+    let mut triggers: Vec<Arc<dyn Trigger>> = Vec::new();
+    jobs.clear();
+    for (job, trigger) in scheduled {
+        jobs.insert(job.id.clone(), job);
+        triggers.push(Arc::new(trigger));
+    }
+
+    let report = scheduler.reload(triggers);
+    for (change, ids) in [
+        ("added", &report.added),
+        ("removed", &report.removed),
+        ("rescheduled", &report.changed),
+    ] {
+        for id in ids {
+            log(format!("{}: {}", id, change));
+        }
+    }
+    log(format!("loaded {} scheduled jobs", jobs.len()));
+}
+
+/// Skips a run due more than [`MISSED_AFTER`] before now, rescheduling its job from now.
+/// Returns whether the run was skipped.
+fn skip_missed(scheduler: &mut Scheduler, id: &str, firetime: i64, now: i64) -> bool {
+    if now.saturating_sub(firetime) <= MISSED_AFTER.as_millis() as i64 {
+        return false;
+    }
+    scheduler.pause_job(id);
+    scheduler.resume_job(id, ResumePolicy::Skip);
+    true
+}
+
+/// What a signal sent to the daemon asks for.
+enum Request {
+    Stop,
+    Reload,
+}
+
+/// The signals the daemon handles.
+#[cfg(unix)]
+struct Signals {
+    terminate: Signal,
+    interrupt: Signal,
+    hangup: Signal,
+}
+
+#[cfg(unix)]
+impl Signals {
+    fn new() -> io::Result<Self> {
+        Ok(Self {
+            terminate: signal(SignalKind::terminate())?,
+            interrupt: signal(SignalKind::interrupt())?,
+            hangup: signal(SignalKind::hangup())?,
+        })
+    }
+
+    async fn recv(&mut self) -> Request {
+        tokio::select! {
+            _ = self.hangup.recv() => Request::Reload,
+            _ = self.terminate.recv() => Request::Stop,
+            _ = self.interrupt.recv() => Request::Stop,
+        }
+    }
+}
+
+#[cfg(not(unix))]
+struct Signals;
+
+#[cfg(not(unix))]
+impl Signals {
+    fn new() -> io::Result<Self> {
+        Ok(Signals)
+    }
+
+    async fn recv(&mut self) -> Request {
+        let _ = tokio::signal::ctrl_c().await;
+        Request::Stop
+    }
+}
+
+async fn serve(options: Options) -> Result<(), String> {
+    let mut last_modified = modified(&options.crontab);
+    let Loaded { reboot, scheduled } = load(&options)?;
+
+    let mut signals = Signals::new().map_err(|e| e.to_string())?;
+    let mut poll = tokio::time::interval(options.poll.unwrap_or(Duration::MAX));
+    poll.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let mut running = JoinSet::new();
+    for job in reboot {
+        running.spawn(run(job, options.shell.clone()));
+    }
+
+    let mut scheduler = Scheduler::new();
+    let mut jobs = HashMap::new();
+    reload(&mut scheduler, &mut jobs, scheduled);
+
+    loop {
+        let reload_now = tokio::select! {
+            (id, firetime) = scheduler.wait_next() => {
+                if let Some(job) = jobs.get(&id) {
+                    let time = DateTime::from_timestamp_millis(firetime)
+                        .map(|time| time.to_rfc3339_opts(SecondsFormat::Secs, true))
+                        .unwrap_or_default();
+                    let now = Utc::now().timestamp_millis();
+                    if skip_missed(&mut scheduler, &id, firetime, now) {
+                        log(format!("{}: missed the run due at {}, skipping", id, time));
+                    } else {
+                        log(format!("{}: due at {}", id, time));
+                        running.spawn(run(job.clone(), options.shell.clone()));
+                    }
+                }
+                false
+            }
+            // reap finished jobs so the set doesn't grow forever
+            Some(_) = running.join_next(), if !running.is_empty() => false,
+            _ = poll.tick(), if options.poll.is_some() => {
+                let modified = modified(&options.crontab);
+                let changed = modified != last_modified;
+                last_modified = modified;
+                changed
+            }
+            request = signals.recv() => match request {
+                Request::Reload => true,
+                Request::Stop => break,
+            },
+        };
+
+        if reload_now {
+            log(format!("reloading {}", options.crontab));
+            match load(&options) {
+                Ok(loaded) => reload(&mut scheduler, &mut jobs, loaded.scheduled),
+                // keep running the current jobs until the file is fixed
+                Err(e) => log(format!("failed to reload: {}", e)),
+            }
+        }
+
+        for (id, reason) in scheduler.take_retired() {
+            match reason {
+                Retired::Completed => log(format!("{}: has no more runs", id)),
+                Retired::Failed(e) => log(format!("{}: {}", id, e)),
+            }
+        }
+    }
+
+    if !running.is_empty() {
+        log(format!(
+            "shutting down, waiting for {} running jobs",
+            running.len()
+        ));
+    }
+    while running.join_next().await.is_some() {}
+    log("stopped");
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let options = match parse_args(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) if e.is_empty() => {
+            println!("{}", USAGE);
+            return ExitCode::SUCCESS;
+        }
+        Err(e) => {
+            eprintln!("error: {}\n{}", e, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    match serve(options).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use cron::clock::ManualClock;
+
+    fn args(s: &str) -> impl Iterator<Item = String> + '_ {
+        s.split_whitespace().map(str::to_string)
+    }
+
+    /// Writes a crontab to a file of its own in the temporary directory.
+    fn crontab(name: &str, text: &str) -> Options {
+        let path = env::temp_dir().join(format!("crond-{}-{}", std::process::id(), name));
+        fs::write(&path, text).unwrap();
+        parse_args(args(path.to_str().unwrap())).unwrap()
+    }
+
+    fn command(command: &str, timeout: Option<Duration>) -> Job {
+        Job {
+            id: command.to_string(),
+            command: command.to_string(),
+            env: vec![("GREETING".to_string(), "hello".to_string())],
+            timeout,
+        }
+    }
+
+    #[test]
+    fn arguments() {
+        let options = parse_args(args("--timeout 30 --shell /bin/bash --poll 0 tab")).unwrap();
+        assert_eq!(options.crontab, "tab");
+        assert_eq!(options.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(options.shell, "/bin/bash");
+        assert_eq!(options.poll, None);
+
+        let options = parse_args(args("tab")).unwrap();
+        assert_eq!(options.timeout, None);
+        assert_eq!(options.poll, Some(Duration::from_secs(5)));
+
+        assert_eq!(parse_args(args("--help")).err().unwrap(), "");
+        assert!(parse_args(args("")).is_err());
+        assert!(parse_args(args("--timeout")).is_err());
+        assert!(parse_args(args("--timeout soon tab")).is_err());
+        assert!(parse_args(args("--verbose tab")).is_err());
+        assert!(parse_args(args("tab other")).is_err());
+    }
+
+    #[test]
+    fn loading() {
+        let options = crontab(
+            "loading",
+            "@reboot echo up\n\
+             @daily echo hi\n\
+             CROND_TIMEOUT=10\n\
+             X=1\n\
+             0 0 * * * ? * echo hi\n",
+        );
+        let Loaded { reboot, scheduled } = load(&options).unwrap();
+        fs::remove_file(&options.crontab).unwrap();
+
+        assert_eq!(reboot.len(), 1);
+        assert_eq!(reboot[0].id, "echo up");
+        assert_eq!(reboot[0].timeout, None);
+
+        let ids: Vec<_> = scheduled.iter().map(|(job, _)| job.id.as_str()).collect();
+        assert_eq!(ids, ["echo hi", "echo hi #2"]);
+        let (job, trigger) = &scheduled[1];
+        assert_eq!(job.timeout, Some(Duration::from_secs(10)));
+        assert_eq!(job.env, [("X".to_string(), "1".to_string())]);
+        assert_eq!(trigger.get_id(), "echo hi #2");
+        assert_eq!(trigger.definition(), "cron 0 0 * * * ? *");
+    }
+
+    #[test]
+    fn load_errors() {
+        let options = crontab("errors", "@daily a\nCROND_TIMEOUT=soon\n@daily b\n");
+        let error = load(&options).err().unwrap();
+        fs::remove_file(&options.crontab).unwrap();
+        assert!(error.contains("line 3"), "{}", error);
+
+        let options = crontab("missing", "");
+        fs::remove_file(&options.crontab).unwrap();
+        assert!(load(&options).is_err());
+    }
+
+    #[tokio::test]
+    async fn output_and_exit_code() {
+        let job = command("echo $GREETING; echo oops >&2; exit 3", None);
+        let outcome = execute(&job, "/bin/sh").await.unwrap();
+        assert_eq!(outcome.status, Status::Exited(3));
+        assert_eq!(
+            outcome.output,
+            [
+                ("stdout", "hello".to_string()),
+                ("stderr", "oops".to_string())
+            ]
+        );
+
+        assert!(execute(&job, "/no/such/shell").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn output_is_kept_while_background_processes_hold_it() {
+        let job = command("echo before; sleep 30 &", None);
+        let started = Instant::now();
+        let outcome = execute(&job, "/bin/sh").await.unwrap();
+        let waited = started.elapsed();
+        assert!(
+            waited >= OUTPUT_GRACE && waited < OUTPUT_GRACE * 2,
+            "{:?}",
+            waited
+        );
+        assert_eq!(outcome.status, Status::Exited(0));
+        assert_eq!(outcome.output, [("stdout", "before".to_string())]);
+    }
+
+    #[test]
+    fn missed_runs_are_skipped() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = ManualClock::new(start);
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock.clone()));
+        let minutely = CronTrigger::new("job".to_string(), "0 * * * * ? *").unwrap();
+        scheduler.add_job(Arc::new(minutely));
+        let minutes = |n| (start + chrono::Duration::minutes(n)).timestamp_millis();
+
+        // the container is suspended for an hour
+        clock.set(start + chrono::Duration::hours(1));
+        let (id, firetime) = scheduler.next_firing().unwrap();
+        assert_eq!(firetime, minutes(1));
+        assert!(skip_missed(&mut scheduler, &id, firetime, minutes(60)));
+        assert_eq!(
+            scheduler.peek_next_firing(),
+            Some((id.clone(), minutes(61)))
+        );
+
+        // runs a little late are still run
+        let (id, firetime) = scheduler.next_firing().unwrap();
+        let late = minutes(61) + 30_000;
+        assert!(!skip_missed(&mut scheduler, &id, firetime, late));
+        assert_eq!(scheduler.peek_next_firing(), Some((id, minutes(62))));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn timeouts_kill_the_started_processes() {
+        let pidfile = env::temp_dir().join(format!("crond-{}-pid", std::process::id()));
+        let job = command(
+            &format!(
+                "echo before; sleep 30 & echo $! > {}; wait",
+                pidfile.display()
+            ),
+            Some(Duration::from_secs(1)),
+        );
+
+        let started = Instant::now();
+        let outcome = execute(&job, "/bin/sh").await.unwrap();
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(outcome.status, Status::TimedOut(Duration::from_secs(1)));
+        assert_eq!(outcome.output, [("stdout", "before".to_string())]);
+
+        let pid = fs::read_to_string(&pidfile).unwrap();
+        fs::remove_file(&pidfile).unwrap();
+        // the background sleep is either reaped already or a zombie waiting for init
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid.trim())).unwrap_or_default();
+        let state = stat.rsplit(") ").next().unwrap_or("").chars().next();
+        assert!(matches!(state, None | Some('Z')), "{}", stat);
+    }
+}
@@ -0,0 +1,612 @@
+//! Calendars excluding dates and times from schedules, like Quartz calendars. A calendar can
+//! exclude single dates, dates recurring every year, time windows recurring every day and
+//! one-off ranges of time.
+//!
+//! Calendars can be built in code, loaded from a simple line based format (see [`Calendar`]'s
+//! `FromStr` implementation) or loaded from the `VEVENT`s of an iCalendar file with
+//! [`Calendar::from_ical`].
+//!
+//! [`Calendar`]: struct.Calendar.html
+//! [`Calendar::from_ical`]: struct.Calendar.html#method.from_ical
+
+use crate::Cron;
+use chrono::{prelude::*, DateTime, Duration, NaiveDate, NaiveTime, Utc};
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+use std::collections::BTreeSet;
+
+/// The max number of excluded periods skipped in one search before giving up. This stops
+/// calendars excluding all time from searching forever.
+const MAX_SKIPS: usize = 100_000;
+
+/// A set of excluded dates and times.
+///
+/// # Example
+/// ```
+/// use cron::calendar::Calendar;
+/// use chrono::prelude::*;
+///
+/// let calendar: Calendar = "
+///     2024-12-26  # a bank holiday
+///     12-25       # christmas, every year
+///     02:00-03:00 # nightly maintenance
+/// "
+/// .parse()
+/// .unwrap();
+///
+/// assert!(calendar.excludes(Utc.with_ymd_and_hms(2030, 12, 25, 12, 0, 0).unwrap()));
+/// assert!(calendar.excludes(Utc.with_ymd_and_hms(2024, 1, 1, 2, 30, 0).unwrap()));
+/// assert!(!calendar.excludes(Utc.with_ymd_and_hms(2024, 1, 1, 3, 0, 0).unwrap()));
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
+pub struct Calendar {
+    dates: BTreeSet<NaiveDate>,
+    annual: BTreeSet<(u32, u32)>,
+    daily: Vec<(NaiveTime, NaiveTime)>,
+    ranges: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+impl Calendar {
+    /// Creates an empty calendar, excluding nothing.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Excludes the whole given date.
+    pub fn exclude_date(&mut self, date: NaiveDate) {
+        self.dates.insert(date);
+    }
+
+    /// Excludes the given month (1-12) and day (1-31) every year. Returns an error if the day
+    /// doesn't exist in the month in any year.
+    pub fn exclude_annual(&mut self, month: u32, day: u32) -> Result<(), CalendarError> {
+        // 2000 is a leap year, so this allows February 29th
+        if NaiveDate::from_ymd_opt(2000, month, day).is_none() {
+            return Err(CalendarError(()));
+        }
+
+        self.annual.insert((month, day));
+        Ok(())
+    }
+
+    /// Excludes the time from `start` (inclusive) to `end` (exclusive) every day. If `end` is
+    /// before `start`, the window wraps around midnight.
+    pub fn exclude_daily(&mut self, start: NaiveTime, end: NaiveTime) {
+        if start != end {
+            self.daily.push((start, end));
+        }
+    }
+
+    /// Excludes the time from `start` (inclusive) to `end` (exclusive).
+    pub fn exclude_range(&mut self, start: DateTime<Utc>, end: DateTime<Utc>) {
+        if start < end {
+            self.ranges.push((start, end));
+        }
+    }
+
+    /// Returns whether the whole given date is excluded by a single or annual date. Daily
+    /// windows and ranges aren't considered.
+    pub fn is_holiday(&self, date: NaiveDate) -> bool {
+        self.dates.contains(&date) || self.annual.contains(&(date.month(), date.day()))
+    }
+
+    /// Returns whether the given time is excluded.
+    pub fn excludes(&self, dt: DateTime<Utc>) -> bool {
+        self.excluded_until(dt).is_some()
+    }
+
+    /// Returns the first time at or after the given time that isn't excluded, or none if no
+    /// such time can be found.
+    pub fn next_included(&self, mut dt: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        for _ in 0..MAX_SKIPS {
+            match self.excluded_until(dt) {
+                Some(end) => dt = end?,
+                None => return Some(dt),
+            }
+        }
+
+        None
+    }
+
+    /// Returns the end of the excluded period containing the given time, or none if the time
+    /// isn't excluded. The end is none itself if it can't be represented.
+    fn excluded_until(&self, dt: DateTime<Utc>) -> Option<Option<DateTime<Utc>>> {
+        let date = dt.date_naive();
+        if self.is_holiday(date) {
+            return Some(
+                date.succ_opt()
+                    .map(|next| next.and_time(NaiveTime::MIN).and_utc()),
+            );
+        }
+
+        let time = dt.time();
+        for &(start, end) in &self.daily {
+            if start < end {
+                if start <= time && time < end {
+                    return Some(Some(date.and_time(end).and_utc()));
+                }
+            } else if time >= start {
+                let next = date.succ_opt().map(|next| next.and_time(end).and_utc());
+                return Some(next);
+            } else if time < end {
+                return Some(Some(date.and_time(end).and_utc()));
+            }
+        }
+
+        self.ranges
+            .iter()
+            .find(|(start, end)| *start <= dt && dt < *end)
+            .map(|&(_, end)| Some(end))
+    }
+
+    /// Loads a calendar from the `VEVENT`s of an iCalendar file.
+    ///
+    /// * All-day events exclude their dates, or the same dates every year if the event has a
+    ///   `RRULE:FREQ=YEARLY` rule.
+    /// * Timed events exclude their time range, or the same time window every day if the event
+    ///   has a `RRULE:FREQ=DAILY` rule.
+    ///
+    /// Times without a `Z` suffix are read as UTC. Times with a `TZID` parameter and other
+    /// recurrence rules aren't supported and result in an error.
+    ///
+    /// # Example
+    /// ```
+    /// use cron::calendar::Calendar;
+    /// use chrono::prelude::*;
+    ///
+    /// let calendar = Calendar::from_ical(
+    ///     "BEGIN:VCALENDAR\r\n\
+    ///      BEGIN:VEVENT\r\n\
+    ///      SUMMARY:Christmas\r\n\
+    ///      DTSTART;VALUE=DATE:20241225\r\n\
+    ///      RRULE:FREQ=YEARLY\r\n\
+    ///      END:VEVENT\r\n\
+    ///      END:VCALENDAR\r\n",
+    /// )
+    /// .unwrap();
+    ///
+    /// assert!(calendar.excludes(Utc.with_ymd_and_hms(2031, 12, 25, 8, 0, 0).unwrap()));
+    /// ```
+    pub fn from_ical(s: &str) -> Result<Self, CalendarParseError> {
+        let mut calendar = Self::new();
+        let mut event: Option<Event> = None;
+
+        for (line, content) in unfold_ical_lines(s) {
+            let error = CalendarParseError { line };
+            let (name, params, value) = split_ical_property(&content).ok_or(error)?;
+
+            match (name.as_str(), &mut event) {
+                ("BEGIN", None) if value.eq_ignore_ascii_case("VEVENT") => {
+                    event = Some(Event::default())
+                }
+                ("END", Some(_)) if value.eq_ignore_ascii_case("VEVENT") => {
+                    event.take().unwrap().apply(&mut calendar).ok_or(error)?
+                }
+                ("DTSTART", Some(event)) => {
+                    event.start = Some(parse_ical_time(params, value, line)?)
+                }
+                ("DTEND", Some(event)) => event.end = Some(parse_ical_time(params, value, line)?),
+                ("RRULE", Some(event)) => {
+                    let freq = value
+                        .split(';')
+                        .find_map(|part| part.strip_prefix("FREQ="))
+                        .ok_or(error)?;
+                    event.freq = Some(match freq {
+                        "YEARLY" => Freq::Yearly,
+                        "DAILY" => Freq::Daily,
+                        _ => return Err(error),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(calendar)
+    }
+}
+
+/// Parses a calendar from a simple line based format. Each line holds one exclusion:
+///
+/// | Line                     | Excludes                                 |
+/// | ------------------------ | ---------------------------------------- |
+/// | `2024-12-25`             | A single date                            |
+/// | `2024-12-24..2024-12-31` | All dates in the range, inclusive        |
+/// | `12-25`                  | A month and day, every year              |
+/// | `02:00-03:30`            | A time window every day, end exclusive   |
+///
+/// Empty lines and anything after a `#` are ignored.
+impl FromStr for Calendar {
+    type Err = CalendarParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut calendar = Self::new();
+
+        for (index, line) in s.lines().enumerate() {
+            let line = match line.split_once('#') {
+                Some((content, _)) => content,
+                None => line,
+            }
+            .trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            calendar
+                .add_line(line)
+                .ok_or(CalendarParseError { line: index + 1 })?;
+        }
+
+        Ok(calendar)
+    }
+}
+
+impl Calendar {
+    fn add_line(&mut self, line: &str) -> Option<()> {
+        const DATE: &str = "%Y-%m-%d";
+
+        if let Some((start, end)) = line.split_once("..") {
+            let start = NaiveDate::parse_from_str(start.trim(), DATE).ok()?;
+            let end = NaiveDate::parse_from_str(end.trim(), DATE).ok()?;
+            for date in start.iter_days().take_while(|&date| date <= end) {
+                self.exclude_date(date);
+            }
+        } else if line.contains(':') {
+            let (start, end) = line.split_once('-')?;
+            self.exclude_daily(parse_time(start.trim())?, parse_time(end.trim())?);
+        } else if let Ok(date) = NaiveDate::parse_from_str(line, DATE) {
+            self.exclude_date(date);
+        } else {
+            let (month, day) = line.split_once('-')?;
+            self.exclude_annual(month.parse().ok()?, day.parse().ok()?)
+                .ok()?;
+        }
+
+        Some(())
+    }
+}
+
+fn parse_time(s: &str) -> Option<NaiveTime> {
+    NaiveTime::parse_from_str(s, "%H:%M:%S")
+        .or_else(|_| NaiveTime::parse_from_str(s, "%H:%M"))
+        .ok()
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Freq {
+    Yearly,
+    Daily,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum IcalTime {
+    Date(NaiveDate),
+    DateTime(DateTime<Utc>),
+}
+
+#[derive(Debug, Default)]
+struct Event {
+    start: Option<IcalTime>,
+    end: Option<IcalTime>,
+    freq: Option<Freq>,
+}
+
+impl Event {
+    /// Adds the event's exclusions to the calendar, or returns none if the event is invalid.
+    fn apply(self, calendar: &mut Calendar) -> Option<()> {
+        match (self.start?, self.end, self.freq) {
+            (IcalTime::Date(start), end, freq) => {
+                // the end date of an all-day event is exclusive, and defaults to the next day
+                let end = match end {
+                    Some(IcalTime::Date(end)) => end,
+                    None => start.succ_opt()?,
+                    Some(IcalTime::DateTime(_)) => return None,
+                };
+                for date in start.iter_days().take_while(|&date| date < end) {
+                    match freq {
+                        None => calendar.exclude_date(date),
+                        Some(Freq::Yearly) => {
+                            calendar.exclude_annual(date.month(), date.day()).ok()?
+                        }
+                        Some(Freq::Daily) => return None,
+                    }
+                }
+            }
+            (IcalTime::DateTime(start), Some(IcalTime::DateTime(end)), freq) => match freq {
+                None => calendar.exclude_range(start, end),
+                Some(Freq::Daily) => calendar.exclude_daily(start.time(), end.time()),
+                Some(Freq::Yearly) => return None,
+            },
+            (IcalTime::DateTime(_), _, _) => return None,
+        }
+
+        Some(())
+    }
+}
+
+/// Unfolds the lines of an iCalendar file, returning each logical line with the line number it
+/// starts on.
+fn unfold_ical_lines(s: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    for (index, line) in s.lines().enumerate() {
+        let line = line.trim_end_matches('\r');
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(continued), Some((_, last))) => last.push_str(continued),
+            _ if line.trim().is_empty() => {}
+            _ => lines.push((index + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+/// Splits an iCalendar content line into its uppercased name, parameters and value.
+fn split_ical_property(line: &str) -> Option<(String, &str, &str)> {
+    let (key, value) = line.split_once(':')?;
+    let (name, params) = key.split_once(';').unwrap_or((key, ""));
+    Some((name.trim().to_ascii_uppercase(), params, value.trim()))
+}
+
+fn parse_ical_time(params: &str, value: &str, line: usize) -> Result<IcalTime, CalendarParseError> {
+    let error = CalendarParseError { line };
+    let mut params = params.split(';');
+    // time zones aren't supported, and reading the time as UTC would shift the exclusion
+    if params.clone().any(|param| {
+        param
+            .get(..5)
+            .is_some_and(|name| name.eq_ignore_ascii_case("TZID="))
+    }) {
+        return Err(error);
+    }
+    let is_date = params.any(|param| param.eq_ignore_ascii_case("VALUE=DATE"));
+
+    if is_date || !value.contains('T') {
+        NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map(IcalTime::Date)
+            .map_err(|_| error)
+    } else {
+        NaiveDateTime::parse_from_str(value.trim_end_matches('Z'), "%Y%m%dT%H%M%S")
+            .map(|dt| IcalTime::DateTime(dt.and_utc()))
+            .map_err(|_| error)
+    }
+}
+
+/// A cron value skipping all times excluded by a calendar.
+///
+/// # Example
+/// ```
+/// use cron::Cron;
+/// use cron::calendar::{Calendar, CalendarCron};
+/// use chrono::prelude::*;
+///
+/// let cron = "0 0 8 ? * MON-FRI *".parse::<Cron>().unwrap();
+/// let holidays = "2024-12-25\n2024-12-26".parse::<Calendar>().unwrap();
+/// let business_days = CalendarCron::new(cron, holidays);
+///
+/// let christmas_eve = Utc.with_ymd_and_hms(2024, 12, 24, 8, 0, 0).unwrap();
+/// assert_eq!(
+///     business_days.next_after(christmas_eve),
+///     Some(Utc.with_ymd_and_hms(2024, 12, 27, 8, 0, 0).unwrap())
+/// );
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone)]
+pub struct CalendarCron {
+    cron: Cron,
+    calendar: Calendar,
+}
+
+impl CalendarCron {
+    /// Creates a cron value skipping the times excluded by the calendar.
+    pub fn new(cron: Cron, calendar: Calendar) -> Self {
+        Self { cron, calendar }
+    }
+
+    /// Returns the underlying cron value.
+    pub fn cron(&self) -> &Cron {
+        &self.cron
+    }
+
+    /// Returns the calendar.
+    pub fn calendar(&self) -> &Calendar {
+        &self.calendar
+    }
+
+    /// Returns whether the cron value matches the given time and the calendar doesn't exclude it.
+    pub fn contains(&self, dt: DateTime<Utc>) -> bool {
+        self.cron.contains(dt) && !self.calendar.excludes(dt)
+    }
+
+    /// Returns the next time the cron value matches that isn't excluded, including the given time.
+    pub fn next_from(&self, start: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        next_from(&self.cron, &self.calendar, start)
+    }
+
+    /// Returns the next time the cron value matches that isn't excluded, after the given time.
+    pub fn next_after(&self, start: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        next_after(&self.cron, &self.calendar, start)
+    }
+}
+
+/// Returns the next time the cron value matches after the given time that the calendar doesn't
+/// exclude.
+pub(crate) fn next_after(
+    cron: &Cron,
+    calendar: &Calendar,
+    start: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let start = start
+        .with_nanosecond(0)?
+        .checked_add_signed(Duration::seconds(1))?;
+    next_from(cron, calendar, start)
+}
+
+fn next_from(cron: &Cron, calendar: &Calendar, start: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let mut candidate = cron.next_from(start)?;
+    for _ in 0..MAX_SKIPS {
+        let included = calendar.next_included(candidate)?;
+        if included == candidate {
+            return Some(candidate);
+        }
+        candidate = cron.next_from(included)?;
+    }
+
+    None
+}
+
+/// An error returned if a calendar exclusion is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalendarError(());
+
+impl Display for CalendarError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        "The calendar exclusion is invalid".fmt(f)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CalendarError {}
+
+/// An error returned if a calendar fails to load.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CalendarParseError {
+    line: usize,
+}
+
+impl CalendarParseError {
+    /// Returns the line number (starting at 1) of the invalid entry.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl Display for CalendarParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid calendar entry on line {}", self.line)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CalendarParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn parse_lines() {
+        let calendar: Calendar = "
+            2024-12-24..2024-12-26 # christmas
+            01-01
+            22:00-06:00
+        "
+        .parse()
+        .unwrap();
+
+        assert!(calendar.is_holiday(NaiveDate::from_ymd_opt(2024, 12, 24).unwrap()));
+        assert!(calendar.is_holiday(NaiveDate::from_ymd_opt(2024, 12, 26).unwrap()));
+        assert!(!calendar.is_holiday(NaiveDate::from_ymd_opt(2024, 12, 27).unwrap()));
+        assert!(calendar.is_holiday(NaiveDate::from_ymd_opt(2031, 1, 1).unwrap()));
+
+        assert!(calendar.excludes(utc(2024, 3, 1, 23, 0)));
+        assert!(calendar.excludes(utc(2024, 3, 1, 5, 59)));
+        assert!(!calendar.excludes(utc(2024, 3, 1, 6, 0)));
+    }
+
+    #[test]
+    fn parse_errors_have_line_numbers() {
+        let err = "2024-12-25\n\n13-01\n".parse::<Calendar>().unwrap_err();
+        assert_eq!(err.line(), 3);
+
+        let err = "2024-12-25\n25:00-26:00".parse::<Calendar>().unwrap_err();
+        assert_eq!(err.line(), 2);
+    }
+
+    #[test]
+    fn next_included_skips_chained_exclusions() {
+        let calendar: Calendar = "2024-12-25\n00:00-01:00".parse().unwrap();
+
+        assert_eq!(
+            calendar.next_included(utc(2024, 12, 25, 12, 0)),
+            Some(utc(2024, 12, 26, 1, 0))
+        );
+        assert_eq!(
+            calendar.next_included(utc(2024, 12, 26, 1, 0)),
+            Some(utc(2024, 12, 26, 1, 0))
+        );
+    }
+
+    #[test]
+    fn invalid_annual_dates() {
+        let mut calendar = Calendar::new();
+        assert_eq!(calendar.exclude_annual(2, 29), Ok(()));
+        assert_eq!(calendar.exclude_annual(2, 30), Err(CalendarError(())));
+        assert_eq!(calendar.exclude_annual(13, 1), Err(CalendarError(())));
+    }
+
+    #[test]
+    fn ical_events() {
+        let calendar = Calendar::from_ical(
+            "BEGIN:VCALENDAR
+BEGIN:VEVENT
+SUMMARY:Company
+  holiday
+DTSTART;VALUE=DATE:20240701
+DTEND;VALUE=DATE:20240703
+END:VEVENT
+BEGIN:VEVENT
+DTSTART:20240101T020000Z
+DTEND:20240101T030000Z
+RRULE:FREQ=DAILY
+END:VEVENT
+BEGIN:VEVENT
+DTSTART:20240801T120000Z
+DTEND:20240801T130000Z
+END:VEVENT
+END:VCALENDAR
+",
+        )
+        .unwrap();
+
+        assert!(calendar.is_holiday(NaiveDate::from_ymd_opt(2024, 7, 1).unwrap()));
+        assert!(calendar.is_holiday(NaiveDate::from_ymd_opt(2024, 7, 2).unwrap()));
+        assert!(!calendar.is_holiday(NaiveDate::from_ymd_opt(2024, 7, 3).unwrap()));
+        assert!(calendar.excludes(utc(2030, 5, 5, 2, 15)));
+        assert!(calendar.excludes(utc(2024, 8, 1, 12, 30)));
+        assert!(!calendar.excludes(utc(2024, 8, 2, 12, 30)));
+    }
+
+    #[test]
+    fn ical_unsupported_rule() {
+        let err = Calendar::from_ical(
+            "BEGIN:VEVENT\nDTSTART;VALUE=DATE:20240701\nRRULE:FREQ=WEEKLY\nEND:VEVENT\n",
+        )
+        .unwrap_err();
+        assert_eq!(err.line(), 3);
+    }
+
+    #[test]
+    fn ical_time_zones_are_rejected() {
+        let err = Calendar::from_ical(
+            "BEGIN:VEVENT\nDTSTART;TZID=Europe/Paris:20240701T090000\n\
+             DTEND;TZID=Europe/Paris:20240701T100000\nEND:VEVENT\n",
+        )
+        .unwrap_err();
+        assert_eq!(err.line(), 2);
+    }
+
+    #[test]
+    fn calendar_cron_skips_excluded_times() {
+        let cron = "0 */30 * * * ? *".parse::<Cron>().unwrap();
+        let calendar: Calendar = "02:00-03:00".parse().unwrap();
+        let cron = CalendarCron::new(cron, calendar);
+
+        assert_eq!(
+            cron.next_after(utc(2024, 1, 1, 1, 30)),
+            Some(utc(2024, 1, 1, 3, 0))
+        );
+        assert!(!cron.contains(utc(2024, 1, 1, 2, 30)));
+        assert!(cron.contains(utc(2024, 1, 1, 3, 30)));
+    }
+}
@@ -0,0 +1,199 @@
+//! Sources of the current time, so schedulers can run on simulated time in tests.
+
+use chrono::{DateTime, Utc};
+use core::future::Future;
+use core::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+/// A future completing once a clock reaches a time, returned by [`Clock::sleep_until`].
+///
+/// [`Clock::sleep_until`]: trait.Clock.html#method.sleep_until
+pub type ClockSleep<'a> = Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
+
+/// A source of the current time.
+pub trait Clock: Send + Sync {
+    /// Returns the current time.
+    fn now(&self) -> DateTime<Utc>;
+
+    /// Returns a future completing once the clock reaches the given time. The default sleeps
+    /// with tokio for the time left until then, as measured when the future is created.
+    fn sleep_until(&self, time: DateTime<Utc>) -> ClockSleep<'_> {
+        let left = (time - self.now()).to_std().unwrap_or(Duration::ZERO);
+        Box::pin(tokio::time::sleep(left))
+    }
+}
+
+/// The system's wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock that only moves when it's set or advanced. Clones share the same time, so a test
+/// can keep a clone to move the time of a scheduler using another. Sleeping on the clock
+/// completes once it's moved past the time slept until.
+///
+/// # Example
+/// ```
+/// use cron::clock::{Clock, ManualClock};
+/// use chrono::prelude::*;
+///
+/// let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+/// let clock = ManualClock::new(start);
+/// clock.advance(chrono::Duration::hours(1));
+/// assert_eq!(clock.now(), Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap());
+/// ```
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Arc<Mutex<DateTime<Utc>>>,
+    /// Notified whenever the time is moved
+    moved: Arc<Notify>,
+}
+
+impl ManualClock {
+    /// Creates a clock stopped at the given time.
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self {
+            now: Arc::new(Mutex::new(now)),
+            moved: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Sets the current time.
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap() = now;
+        self.moved.notify_waiters();
+    }
+
+    /// Moves the current time by the given duration.
+    pub fn advance(&self, duration: chrono::Duration) {
+        *self.now.lock().unwrap() += duration;
+        self.moved.notify_waiters();
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap()
+    }
+
+    fn sleep_until(&self, time: DateTime<Utc>) -> ClockSleep<'_> {
+        Box::pin(async move {
+            loop {
+                // listen before checking so a move in between isn't missed
+                let moved = self.moved.notified();
+                if self.now() >= time {
+                    return;
+                }
+                moved.await;
+            }
+        })
+    }
+}
+
+/// A clock following tokio's time from the wall clock time it was created at, so it follows
+/// `tokio::time::pause` and `tokio::time::advance` in tests.
+#[derive(Debug, Clone, Copy)]
+pub struct TokioClock {
+    /// The wall clock time at `origin`
+    wall: DateTime<Utc>,
+    origin: Instant,
+}
+
+impl TokioClock {
+    /// Creates a clock starting at the current system time.
+    ///
+    /// Must be called within a tokio runtime if tokio's time is paused.
+    pub fn new() -> Self {
+        Self::starting_at(Utc::now())
+    }
+
+    /// Creates a clock starting at the given time.
+    pub fn starting_at(start: DateTime<Utc>) -> Self {
+        Self {
+            wall: start,
+            origin: Instant::now(),
+        }
+    }
+
+    /// Returns the tokio instant at which the clock shows the given time, or the clock's
+    /// start if the time is before it.
+    pub fn instant_at(&self, time: DateTime<Utc>) -> Instant {
+        self.origin + (time - self.wall).to_std().unwrap_or(Duration::ZERO)
+    }
+}
+
+impl Default for TokioClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for TokioClock {
+    fn now(&self) -> DateTime<Utc> {
+        let elapsed = Instant::now().saturating_duration_since(self.origin);
+        chrono::Duration::from_std(elapsed)
+            .ok()
+            .and_then(|elapsed| self.wall.checked_add_signed(elapsed))
+            .unwrap_or(self.wall)
+    }
+
+    fn sleep_until(&self, time: DateTime<Utc>) -> ClockSleep<'_> {
+        Box::pin(tokio::time::sleep_until(self.instant_at(time)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn manual_clock_clones_share_time() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = ManualClock::new(start);
+        let shared = clock.clone();
+        shared.advance(chrono::Duration::days(2));
+        assert_eq!(clock.now(), start + chrono::Duration::days(2));
+        clock.set(start);
+        assert_eq!(shared.now(), start);
+    }
+
+    #[tokio::test]
+    async fn manual_clock_sleeps_until_moved() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = ManualClock::new(start);
+        let mover = clock.clone();
+        let handle = tokio::spawn(async move {
+            for _ in 0..3 {
+                tokio::task::yield_now().await;
+                mover.advance(chrono::Duration::minutes(1));
+            }
+        });
+
+        clock
+            .sleep_until(start + chrono::Duration::minutes(3))
+            .await;
+        assert_eq!(clock.now(), start + chrono::Duration::minutes(3));
+        handle.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn tokio_clock_follows_paused_time() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = TokioClock::starting_at(start);
+        tokio::time::advance(Duration::from_secs(90)).await;
+        assert_eq!(clock.now(), start + chrono::Duration::seconds(90));
+        assert_eq!(
+            clock.instant_at(start + chrono::Duration::seconds(100)),
+            Instant::now() + Duration::from_secs(10)
+        );
+    }
+}
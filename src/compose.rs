@@ -0,0 +1,230 @@
+//! Sets of times combining crons, for schedules a single expression can't express.
+
+use crate::Cron;
+use chrono::{DateTime, Utc};
+
+/// The most candidate times searched for a time in an intersection or exclusion before giving
+/// up, so sets without any more times don't search until the end of time.
+pub(crate) const MAX_STEPS: usize = 10_000;
+
+/// Sets of times searched by intersections and exclusions, shared by [`CronSet`] and the
+/// scheduler's trigger combinators.
+pub(crate) trait Times {
+    type Time: Copy + Ord;
+    type Error;
+
+    /// Returns the first time in the set at or after the given time.
+    fn first_from(&self, start: Self::Time) -> Result<Option<Self::Time>, Self::Error>;
+
+    /// Returns the first time in the set after the given time.
+    fn first_after(&self, time: Self::Time) -> Result<Option<Self::Time>, Self::Error>;
+
+    /// Returns whether the set contains the given time.
+    fn contains_time(&self, time: Self::Time) -> Result<bool, Self::Error>;
+
+    /// Returns the error for a search giving up after [`MAX_STEPS`] candidates.
+    fn gave_up() -> Self::Error;
+}
+
+/// Returns the first time at or after `start` that all of the sets contain. Without sets,
+/// there's none.
+pub(crate) fn first_common<S: Times>(
+    sets: &[S],
+    start: S::Time,
+) -> Result<Option<S::Time>, S::Error> {
+    let mut start = start;
+    for _ in 0..MAX_STEPS {
+        // the latest of the next times is the earliest time that may be common
+        let mut next = None;
+        for set in sets {
+            match set.first_from(start)? {
+                Some(time) => next = next.max(Some(time)),
+                None => return Ok(None),
+            }
+        }
+        let next = match next {
+            Some(next) => next,
+            None => return Ok(None),
+        };
+
+        let mut common = true;
+        for set in sets {
+            common = common && set.contains_time(next)?;
+        }
+        if common {
+            return Ok(Some(next));
+        }
+        start = next;
+    }
+    Err(S::gave_up())
+}
+
+/// Returns the first time at or after `start` in `set` that `except` doesn't contain.
+pub(crate) fn first_except<S: Times>(
+    set: &S,
+    except: &S,
+    start: S::Time,
+) -> Result<Option<S::Time>, S::Error> {
+    let mut next = set.first_from(start)?;
+    for _ in 0..MAX_STEPS {
+        match next {
+            Some(time) if except.contains_time(time)? => next = set.first_after(time)?,
+            _ => return Ok(next),
+        }
+    }
+    Err(S::gave_up())
+}
+
+/// A set of times made of crons combined by union, intersection and exclusion.
+///
+/// # Example
+/// ```
+/// use cron::compose::CronSet;
+/// use cron::Cron;
+/// use chrono::prelude::*;
+///
+/// // every 15 minutes, except during the 02:00 to 03:00 maintenance
+/// let every = "0 */15 * * * ? *".parse::<Cron>().unwrap();
+/// let maintenance = "* * 2 * * ? *".parse::<Cron>().unwrap();
+/// let set = CronSet::Except(Box::new(every.into()), Box::new(maintenance.into()));
+///
+/// let start = Utc.with_ymd_and_hms(2024, 1, 1, 1, 45, 0).unwrap();
+/// let next = Utc.with_ymd_and_hms(2024, 1, 1, 3, 0, 0).unwrap();
+/// assert_eq!(set.next_after(start), Some(next));
+/// ```
+#[derive(Debug, Clone)]
+pub enum CronSet {
+    /// The times of a cron
+    Cron(Cron),
+    /// The times in any of the sets
+    AnyOf(Vec<CronSet>),
+    /// The times in all of the sets. Without sets, this contains no times.
+    AllOf(Vec<CronSet>),
+    /// The times in the first set that aren't in the second
+    Except(Box<CronSet>, Box<CronSet>),
+}
+
+impl CronSet {
+    /// Returns whether the set contains the given time.
+    pub fn contains(&self, dt: DateTime<Utc>) -> bool {
+        match self {
+            CronSet::Cron(cron) => cron.contains(dt),
+            CronSet::AnyOf(sets) => sets.iter().any(|set| set.contains(dt)),
+            CronSet::AllOf(sets) => !sets.is_empty() && sets.iter().all(|set| set.contains(dt)),
+            CronSet::Except(set, except) => set.contains(dt) && !except.contains(dt),
+        }
+    }
+
+    /// Returns the first time in the set at or after the given time.
+    ///
+    /// Intersections and exclusions search candidate times from their sets, and return `None`
+    /// if none of the first 10,000 candidates match.
+    pub fn next_from(&self, start: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self {
+            CronSet::Cron(cron) => cron.next_from(start),
+            CronSet::AnyOf(sets) => sets.iter().filter_map(|set| set.next_from(start)).min(),
+            CronSet::AllOf(sets) => first_common(sets, start).ok().flatten(),
+            CronSet::Except(set, except) => first_except(&**set, &**except, start).ok().flatten(),
+        }
+    }
+
+    /// Returns the first time in the set after the given time.
+    pub fn next_after(&self, start: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_from(crate::next_second(crate::second_floor(start))?)
+    }
+}
+
+impl Times for CronSet {
+    type Time = DateTime<Utc>;
+    /// Cron sets have no errors, besides searches giving up
+    type Error = ();
+
+    fn first_from(&self, start: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, ()> {
+        Ok(self.next_from(start))
+    }
+
+    fn first_after(&self, time: DateTime<Utc>) -> Result<Option<DateTime<Utc>>, ()> {
+        Ok(self.next_after(time))
+    }
+
+    fn contains_time(&self, time: DateTime<Utc>) -> Result<bool, ()> {
+        Ok(self.contains(time))
+    }
+
+    fn gave_up() {}
+}
+
+impl From<Cron> for CronSet {
+    fn from(cron: Cron) -> Self {
+        CronSet::Cron(cron)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn set(s: &str) -> CronSet {
+        s.parse::<Cron>().unwrap().into()
+    }
+
+    fn next_after(set: &CronSet, start: DateTime<Utc>, n: usize) -> Vec<String> {
+        let mut times = Vec::new();
+        let mut start = start;
+        while let Some(next) = set.next_after(start).filter(|_| times.len() < n) {
+            times.push(next.format("%a %Y-%m-%d %H:%M").to_string());
+            start = next;
+        }
+        times
+    }
+
+    #[test]
+    fn unions_intersections_and_exclusions() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let weekdays_or_noon = CronSet::AnyOf(vec![
+            set("0 0 9 ? * MON-FRI *"),
+            set("0 0 9 ? * MON *"),
+            set("0 0 12 ? * SAT *"),
+        ]);
+        assert_eq!(
+            next_after(&weekdays_or_noon, start, 3),
+            [
+                "Mon 2024-01-01 09:00",
+                "Tue 2024-01-02 09:00",
+                "Wed 2024-01-03 09:00"
+            ]
+        );
+        let saturday = Utc.with_ymd_and_hms(2024, 1, 6, 12, 0, 0).unwrap();
+        assert!(weekdays_or_noon.contains(saturday));
+
+        // the 13th of the month falling on a Friday
+        let friday_13th = CronSet::AllOf(vec![set("0 0 0 13 * ? *"), set("0 0 0 ? * FRI *")]);
+        assert_eq!(
+            next_after(&friday_13th, start, 2),
+            ["Fri 2024-09-13 00:00", "Fri 2024-12-13 00:00"]
+        );
+        assert!(!CronSet::AllOf(Vec::new()).contains(start));
+
+        let outside_maintenance = CronSet::Except(
+            Box::new(set("0 */30 * * * ? *")),
+            Box::new(set("* * 1-2 * * ? *")),
+        );
+        assert_eq!(
+            next_after(&outside_maintenance, start, 3),
+            [
+                "Mon 2024-01-01 00:30",
+                "Mon 2024-01-01 03:00",
+                "Mon 2024-01-01 03:30"
+            ]
+        );
+    }
+
+    #[test]
+    fn disjoint_intersections_end() {
+        let never = CronSet::AllOf(vec![set("0 0 0 * * ? *"), set("0 0 12 * * ? *")]);
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(never.next_after(start), None);
+    }
+}
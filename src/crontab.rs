@@ -0,0 +1,345 @@
+//! Parsing of whole crontab files. A crontab is made of lines that are either blank,
+//! comments starting with `#`, `KEY=value` environment assignments or entries.
+//!
+//! An entry is a schedule followed by a command. The schedule is either the 7 fields of a
+//! cron expression (see [`CronExpr`]) or one of the nicknames `@reboot`, `@yearly`,
+//! `@annually`, `@monthly`, `@weekly`, `@daily`, `@midnight` and `@hourly`. Fields may be
+//! separated by any mix of spaces and tabs, and a `#` preceded by whitespace and outside of
+//! quotes starts a trailing comment.
+//!
+//! [`CronExpr`]: ../parse/struct.CronExpr.html
+
+use crate::parse::{CronExpr, ParseOptions};
+use crate::Cron;
+use core::fmt::{self, Display, Formatter};
+use core::str::FromStr;
+
+/// The number of fields in a cron expression.
+const FIELDS: usize = 7;
+
+/// A parsed crontab file.
+///
+/// # Example
+/// ```
+/// use cron::crontab::{Crontab, Schedule};
+///
+/// let crontab: Crontab = "
+///     MAILTO=ops@example.com # backup reports
+///     0 30 2 * * ?\t* /usr/bin/backup --full  # nightly
+///     @reboot /usr/bin/backup --check
+/// "
+/// .parse()
+/// .unwrap();
+///
+/// let entries = crontab.entries();
+/// assert_eq!(entries.len(), 2);
+/// assert_eq!(entries[0].line(), 3);
+/// assert_eq!(entries[0].command(), "/usr/bin/backup --full");
+/// assert_eq!(entries[0].env(), [("MAILTO".to_string(), "ops@example.com".to_string())]);
+/// assert!(entries[0].cron().is_some());
+/// assert_eq!(entries[1].schedule(), &Schedule::Reboot);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Crontab {
+    env: Vec<(String, String)>,
+    entries: Vec<CrontabEntry>,
+}
+
+impl Crontab {
+    /// Parses a crontab, parsing each entry's cron expression with the given options.
+    pub fn parse_with(options: ParseOptions, s: &str) -> Result<Self, CrontabParseError> {
+        let mut crontab = Self::default();
+
+        for (index, line) in s.lines().enumerate() {
+            let line_number = index + 1;
+            let error = CrontabParseError { line: line_number };
+
+            let content = strip_comment(line).trim();
+            if content.is_empty() {
+                continue;
+            }
+
+            if let Some((name, value)) = env_assignment(content) {
+                set_env(&mut crontab.env, name, value);
+                continue;
+            }
+
+            let (source, schedule, command) = if let Some(rest) = content.strip_prefix('@') {
+                let (nickname, command) = split_fields(rest, 1).ok_or(error)?;
+                let schedule = Schedule::from_nickname(nickname[0]).ok_or(error)?;
+                (format!("@{}", nickname[0]), schedule, command)
+            } else {
+                let (fields, command) = split_fields(content, FIELDS).ok_or(error)?;
+                let source = fields.join(" ");
+                let expr = CronExpr::parse_with(options, &source).map_err(|_| error)?;
+                (source, Schedule::Cron(Box::new(expr)), command)
+            };
+
+            crontab.entries.push(CrontabEntry {
+                line: line_number,
+                source,
+                schedule,
+                command: command.to_string(),
+                env: crontab.env.clone(),
+            });
+        }
+
+        Ok(crontab)
+    }
+
+    /// Returns the entries in the order they appear in the file.
+    pub fn entries(&self) -> &[CrontabEntry] {
+        &self.entries
+    }
+
+    /// Returns the environment as set by the end of the file.
+    pub fn env(&self) -> &[(String, String)] {
+        &self.env
+    }
+}
+
+impl FromStr for Crontab {
+    type Err = CrontabParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(ParseOptions::default(), s)
+    }
+}
+
+/// A single entry of a crontab.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CrontabEntry {
+    line: usize,
+    /// The schedule as written, like `@daily` or `0 0 12 * * ? *`
+    source: String,
+    schedule: Schedule,
+    command: String,
+    env: Vec<(String, String)>,
+}
+
+impl CrontabEntry {
+    /// Returns the line number (starting at 1) of the entry.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns when the entry runs.
+    pub fn schedule(&self) -> &Schedule {
+        &self.schedule
+    }
+
+    /// Returns the schedule as written, like `@daily` or `0 0 12 * * ? *`.
+    pub fn schedule_source(&self) -> &str {
+        &self.source
+    }
+
+    /// Returns the entry's cron, or `None` for `@reboot` entries.
+    pub fn cron(&self) -> Option<Cron> {
+        match &self.schedule {
+            Schedule::Reboot => None,
+            Schedule::Cron(expr) => Some(Cron::new((**expr).clone())),
+        }
+    }
+
+    /// Returns the command, without any trailing comment.
+    pub fn command(&self) -> &str {
+        &self.command
+    }
+
+    /// Returns the environment assignments made before the entry, in the order they were
+    /// first made.
+    pub fn env(&self) -> &[(String, String)] {
+        &self.env
+    }
+}
+
+/// When a crontab entry runs.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Schedule {
+    /// Runs once when the daemon starts.
+    Reboot,
+    /// Runs at the times of a cron expression. Nicknames other than `@reboot` are expanded
+    /// to their expressions.
+    Cron(Box<CronExpr>),
+}
+
+impl Schedule {
+    fn from_nickname(nickname: &str) -> Option<Self> {
+        let expr = match nickname.to_ascii_lowercase().as_str() {
+            "reboot" => return Some(Schedule::Reboot),
+            "yearly" | "annually" => "0 0 0 1 1 ? *",
+            "monthly" => "0 0 0 1 * ? *",
+            "weekly" => "0 0 0 ? * SUN *",
+            "daily" | "midnight" => "0 0 0 * * ? *",
+            "hourly" => "0 0 * * * ? *",
+            _ => return None,
+        };
+        expr.parse().ok().map(|expr| Schedule::Cron(Box::new(expr)))
+    }
+}
+
+/// Removes a trailing comment, which starts at a `#` at the start of the line or after
+/// whitespace, outside of single or double quotes.
+fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut after_space = true;
+
+    for (i, c) in line.char_indices() {
+        match (quote, c) {
+            (None, '#') if after_space => return &line[..i],
+            (None, '\'' | '"') => quote = Some(c),
+            (Some(q), c) if q == c => quote = None,
+            _ => {}
+        }
+        after_space = c.is_whitespace();
+    }
+
+    line
+}
+
+/// Splits a `KEY=value` line, removing matching quotes around the value.
+fn env_assignment(line: &str) -> Option<(&str, &str)> {
+    let (name, value) = line.split_once('=')?;
+    let name = name.trim();
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return None;
+    }
+
+    let value = value.trim();
+    let unquoted = ['"', '\''].iter().find_map(|&q| {
+        value
+            .strip_prefix(q)
+            .and_then(|value| value.strip_suffix(q))
+    });
+    Some((name, unquoted.unwrap_or(value)))
+}
+
+fn set_env(env: &mut Vec<(String, String)>, name: &str, value: &str) {
+    match env.iter_mut().find(|(key, _)| key == name) {
+        Some((_, old)) => *old = value.to_string(),
+        None => env.push((name.to_string(), value.to_string())),
+    }
+}
+
+/// Splits off `n` whitespace separated fields, returning them and the non-empty rest.
+fn split_fields(s: &str, n: usize) -> Option<(Vec<&str>, &str)> {
+    let mut fields = Vec::with_capacity(n);
+    let mut rest = s.trim_start();
+
+    for _ in 0..n {
+        let (field, tail) = rest.split_once(char::is_whitespace)?;
+        fields.push(field);
+        rest = tail.trim_start();
+    }
+
+    let rest = rest.trim_end();
+    if rest.is_empty() {
+        None
+    } else {
+        Some((fields, rest))
+    }
+}
+
+/// An error indicating that a crontab failed to parse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CrontabParseError {
+    line: usize,
+}
+
+impl CrontabParseError {
+    /// Returns the line number (starting at 1) of the invalid line.
+    pub fn line(&self) -> usize {
+        self.line
+    }
+}
+
+impl Display for CrontabParseError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Invalid crontab entry on line {}", self.line)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CrontabParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Crontab {
+        s.parse().unwrap()
+    }
+
+    fn error_line(s: &str) -> usize {
+        s.parse::<Crontab>().unwrap_err().line()
+    }
+
+    #[test]
+    fn tabs_and_trailing_comments() {
+        let crontab = parse("0\t0\t12 * *\t?\t*\techo '# not a comment' \"#\" a#b # comment");
+        let entry = &crontab.entries()[0];
+        assert_eq!(entry.command(), "echo '# not a comment' \"#\" a#b");
+        assert_eq!(
+            entry.schedule(),
+            &Schedule::Cron(Box::new("0 0 12 * * ? *".parse().unwrap()))
+        );
+        assert_eq!(entry.schedule_source(), "0 0 12 * * ? *");
+    }
+
+    #[test]
+    fn nth_weekday_is_not_a_comment() {
+        let crontab = parse("0 0 12 ? * 6#3 * run");
+        assert_eq!(
+            crontab.entries()[0].schedule(),
+            &Schedule::Cron(Box::new("0 0 12 ? * 6#3 *".parse().unwrap()))
+        );
+    }
+
+    #[test]
+    fn nicknames() {
+        let crontab = parse("@Daily a\n@midnight b\n@reboot c\n@hourly d");
+        let entries = crontab.entries();
+        assert_eq!(entries[0].schedule(), entries[1].schedule());
+        assert_eq!(
+            entries[0].schedule(),
+            &Schedule::Cron(Box::new("0 0 0 * * ? *".parse().unwrap()))
+        );
+        assert_eq!(entries[2].schedule(), &Schedule::Reboot);
+        assert!(entries[2].cron().is_none());
+        assert_eq!(entries[3].command(), "d");
+        assert_eq!(entries[0].schedule_source(), "@Daily");
+    }
+
+    #[test]
+    fn environment() {
+        let crontab =
+            parse("SHELL=/bin/sh\n@daily a\nPATH = \"/usr/bin:/bin\"\nSHELL='/bin/bash'\n@daily b");
+        let entries = crontab.entries();
+        assert_eq!(
+            entries[0].env(),
+            [("SHELL".to_string(), "/bin/sh".to_string())]
+        );
+        assert_eq!(
+            entries[1].env(),
+            [
+                ("SHELL".to_string(), "/bin/bash".to_string()),
+                ("PATH".to_string(), "/usr/bin:/bin".to_string())
+            ]
+        );
+        assert_eq!(crontab.env(), entries[1].env());
+    }
+
+    #[test]
+    fn error_lines() {
+        assert_eq!(error_line("# ok\n\n0 0 12 * * ? *"), 3);
+        assert_eq!(error_line("@daily a\n0 0 25 * * ? * b"), 2);
+        assert_eq!(error_line("@sometimes a"), 1);
+        assert_eq!(error_line("@daily  # no command"), 1);
+        assert_eq!(error_line("1BAD=x"), 1);
+    }
+}
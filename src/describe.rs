@@ -0,0 +1,377 @@
+//! English descriptions of cron expressions.
+
+use crate::parse::{
+    BusinessDay, CronExpr, DayMatch, DayOfMonthExpr, DayOfWeekExpr, Expr, ExprValue, Exprs, Last,
+    OrsExpr, Step,
+};
+use core::fmt::{self, Display, Formatter};
+
+const MONTHS: [&str; 12] = [
+    "January",
+    "February",
+    "March",
+    "April",
+    "May",
+    "June",
+    "July",
+    "August",
+    "September",
+    "October",
+    "November",
+    "December",
+];
+
+const WEEKDAYS: [&str; 7] = [
+    "Sunday",
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+];
+
+impl CronExpr {
+    /// Returns a value displaying the expression in English.
+    ///
+    /// # Example
+    /// ```
+    /// use cron::parse::CronExpr;
+    ///
+    /// let expr = "0 30 9 ? * MON-FRI *".parse::<CronExpr>().unwrap();
+    /// assert_eq!(expr.describe().to_string(), "At 09:30, on Monday through Friday");
+    /// ```
+    pub fn describe(&self) -> Description<'_> {
+        Description(self)
+    }
+}
+
+/// An English description of a cron expression, created by [`CronExpr::describe`].
+///
+/// [`CronExpr::describe`]: ../parse/struct.CronExpr.html#method.describe
+#[derive(Debug, Clone, Copy)]
+pub struct Description<'a>(&'a CronExpr);
+
+impl Display for Description<'_> {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let expr = self.0;
+        let mut parts = Vec::new();
+
+        match (&expr.seconds, &expr.minutes, &expr.hours) {
+            (Expr::Many(s), Expr::Many(m), Expr::Many(h))
+                if s.tail.is_empty() && m.tail.is_empty() && h.tail.is_empty() =>
+            {
+                match (s.first, m.first, h.first) {
+                    (OrsExpr::One(s), OrsExpr::One(m), OrsExpr::One(h)) => {
+                        let (s, m, h) = (u32::from(s), u32::from(m), u32::from(h));
+                        parts.push(if s == 0 {
+                            format!("At {:02}:{:02}", h, m)
+                        } else {
+                            format!("At {:02}:{:02}:{:02}", h, m, s)
+                        });
+                    }
+                    _ => time_parts(expr, &mut parts),
+                }
+            }
+            _ => time_parts(expr, &mut parts),
+        }
+
+        let days: Vec<String> = [dom_part(&expr.doms), dow_part(&expr.dows)]
+            .into_iter()
+            .flatten()
+            .collect();
+        if !days.is_empty() {
+            let joiner = match expr.day_match {
+                DayMatch::Or => " or ",
+                DayMatch::And => " and ",
+            };
+            parts.push(format!("on {}", days.join(joiner)));
+        }
+
+        if let Expr::Many(months) = &expr.months {
+            parts.push(format!("in {}", list(months, &MONTH)));
+        }
+        if let Expr::Many(years) = &expr.years {
+            parts.push(format!("in {}", list(years, &YEAR)));
+        }
+
+        parts.join(", ").fmt(f)
+    }
+}
+
+fn time_parts(expr: &CronExpr, parts: &mut Vec<String>) {
+    let seconds = match &expr.seconds {
+        Expr::All => "every second".to_string(),
+        Expr::Many(exprs) => list(exprs, &SECOND),
+    };
+    parts.push(format!("At {}", seconds));
+
+    match &expr.minutes {
+        Expr::All if matches!(expr.seconds, Expr::All) => {}
+        Expr::All => parts.push("every minute".to_string()),
+        Expr::Many(exprs) => parts.push(list(exprs, &MINUTE)),
+    }
+    if let Expr::Many(exprs) = &expr.hours {
+        parts.push(list(exprs, &HOUR));
+    }
+}
+
+fn dom_part(expr: &DayOfMonthExpr) -> Option<String> {
+    Some(match expr {
+        DayOfMonthExpr::Last(Last::Day) => "the last day of the month".to_string(),
+        DayOfMonthExpr::Last(Last::Weekday) => "the last weekday of the month".to_string(),
+        DayOfMonthExpr::Last(Last::Offset(offset)) => {
+            format!(
+                "{} before the last day of the month",
+                days(u32::from(*offset))
+            )
+        }
+        DayOfMonthExpr::Last(Last::OffsetWeekday(offset)) => format!(
+            "the weekday closest to {} before the last day of the month",
+            days(u32::from(*offset))
+        ),
+        DayOfMonthExpr::ClosestWeekday(day) => {
+            format!(
+                "the weekday closest to day {} of the month",
+                u32::from(*day) + 1
+            )
+        }
+        DayOfMonthExpr::BusinessDay(BusinessDay::Nth(n)) => {
+            format!("the {} business day of the month", ordinal(u32::from(*n)))
+        }
+        DayOfMonthExpr::BusinessDay(BusinessDay::NthLast(n)) => match u32::from(*n) {
+            1 => "the last business day of the month".to_string(),
+            n => format!("the {} to last business day of the month", ordinal(n)),
+        },
+        DayOfMonthExpr::Many(exprs) => format!("{} of the month", list(exprs, &DAY_OF_MONTH)),
+        _ => return None,
+    })
+}
+
+fn dow_part(expr: &DayOfWeekExpr) -> Option<String> {
+    Some(match expr {
+        DayOfWeekExpr::Last(day) => {
+            format!("the last {} of the month", weekday(u32::from(*day)))
+        }
+        DayOfWeekExpr::Nth(day, n) => format!(
+            "the {} {} of the month",
+            ordinal(u32::from(*n)),
+            weekday(u32::from(*day))
+        ),
+        DayOfWeekExpr::Many(exprs) => list(exprs, &DAY_OF_WEEK),
+        _ => return None,
+    })
+}
+
+/// How the values of a field are named.
+struct Unit {
+    singular: &'static str,
+    plural: &'static str,
+    /// Whether values are shown by name only, like months, or after the unit, like seconds.
+    named: bool,
+    name: fn(u32) -> String,
+}
+
+const SECOND: Unit = Unit::numbered("second", "seconds");
+const MINUTE: Unit = Unit::numbered("minute", "minutes");
+const HOUR: Unit = Unit::numbered("hour", "hours");
+const DAY_OF_MONTH: Unit = Unit {
+    singular: "day",
+    plural: "days",
+    named: false,
+    name: |d| (d + 1).to_string(),
+};
+const YEAR: Unit = Unit {
+    singular: "year",
+    plural: "years",
+    named: true,
+    name: number,
+};
+const MONTH: Unit = Unit {
+    singular: "month",
+    plural: "months",
+    named: true,
+    name: |m| MONTHS[m as usize].to_string(),
+};
+const DAY_OF_WEEK: Unit = Unit {
+    singular: "day of the week",
+    plural: "days of the week",
+    named: true,
+    name: weekday,
+};
+
+impl Unit {
+    const fn numbered(singular: &'static str, plural: &'static str) -> Self {
+        Unit {
+            singular,
+            plural,
+            named: false,
+            name: number,
+        }
+    }
+}
+
+/// Describes a list of values, ranges and steps.
+fn list<E>(exprs: &Exprs<E>, unit: &Unit) -> String
+where
+    E: ExprValue + Copy + PartialEq,
+    u32: From<E>,
+{
+    let name = unit.name;
+
+    // a list of single values shares the unit, like "seconds 0, 15 and 30"
+    let ones: Option<Vec<String>> = exprs
+        .iter()
+        .map(|expr| match expr {
+            OrsExpr::One(value) => Some(name(u32::from(*value))),
+            _ => None,
+        })
+        .collect();
+    if let Some(ones) = ones {
+        let label = if ones.len() == 1 {
+            unit.singular
+        } else {
+            unit.plural
+        };
+        return prefixed(label, &join(&ones), !unit.named);
+    }
+
+    let items: Vec<String> = exprs
+        .iter()
+        .map(|expr| match *expr {
+            OrsExpr::One(value) => prefixed(unit.singular, &name(u32::from(value)), !unit.named),
+            OrsExpr::Range(start, end) => prefixed(
+                unit.plural,
+                &format!(
+                    "{} through {}",
+                    name(u32::from(start)),
+                    name(u32::from(end))
+                ),
+                !unit.named,
+            ),
+            OrsExpr::Step { start, end, step } => {
+                let every = match <u32 as From<Step<E>>>::from(step) {
+                    1 => unit.singular.to_string(),
+                    step => format!("{} {}", step, unit.plural),
+                };
+                if start == E::min() && end == E::max() {
+                    format!("every {}", every)
+                } else {
+                    format!(
+                        "every {} from {} through {}",
+                        every,
+                        name(u32::from(start)),
+                        name(u32::from(end))
+                    )
+                }
+            }
+        })
+        .collect();
+    join(&items)
+}
+
+fn prefixed(unit: &str, values: &str, with_unit: bool) -> String {
+    if with_unit {
+        format!("{} {}", unit, values)
+    } else {
+        values.to_string()
+    }
+}
+
+/// Joins items like "a, b and c".
+fn join(items: &[String]) -> String {
+    match items {
+        [] => String::new(),
+        [one] => one.clone(),
+        [init @ .., last] => format!("{} and {}", init.join(", "), last),
+    }
+}
+
+fn number(n: u32) -> String {
+    n.to_string()
+}
+
+fn weekday(day: u32) -> String {
+    WEEKDAYS[day as usize].to_string()
+}
+
+fn days(n: u32) -> String {
+    match n {
+        1 => "1 day".to_string(),
+        n => format!("{} days", n),
+    }
+}
+
+fn ordinal(n: u32) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{}{}", n, suffix)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parse::{CronExpr, DayMatch, ParseOptions};
+
+    fn describe(s: &str) -> String {
+        s.parse::<CronExpr>().unwrap().describe().to_string()
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(describe("0 0 9 * * ? *"), "At 09:00");
+        assert_eq!(describe("30 15 23 * * ? *"), "At 23:15:30");
+        assert_eq!(describe("* * * * * ? *"), "At every second");
+        assert_eq!(describe("0 * * * * ? *"), "At second 0, every minute");
+        assert_eq!(
+            describe("0 */5 9-17 * * ? *"),
+            "At second 0, every 5 minutes, hours 9 through 17"
+        );
+        assert_eq!(
+            describe("0,15,30 10-20/2 * * * ? *"),
+            "At seconds 0, 15 and 30, every 2 minutes from 10 through 20"
+        );
+    }
+
+    #[test]
+    fn days() {
+        assert_eq!(
+            describe("0 0 0 1 1 ? *"),
+            "At 00:00, on day 1 of the month, in January"
+        );
+        assert_eq!(
+            describe("0 0 12 ? * 6#3 2024-2026"),
+            "At 12:00, on the 3rd Friday of the month, in 2024 through 2026"
+        );
+        assert_eq!(
+            describe("0 0 12 L-3 * ? *"),
+            "At 12:00, on 3 days before the last day of the month"
+        );
+        assert_eq!(
+            describe("0 0 12 BD-1 * ? *"),
+            "At 12:00, on the last business day of the month"
+        );
+        assert_eq!(
+            describe("0 0 12 2BD * ? *"),
+            "At 12:00, on the 2nd business day of the month"
+        );
+        assert_eq!(
+            describe("0 0 12 ? 1,3 MON,WED *"),
+            "At 12:00, on Monday and Wednesday, in January and March"
+        );
+
+        let expr = CronExpr::parse_with(
+            ParseOptions::new().both_days(DayMatch::And),
+            "0 0 12 1-7 * FRI *",
+        )
+        .unwrap();
+        assert_eq!(
+            expr.describe().to_string(),
+            "At 12:00, on days 1 through 7 of the month and Friday"
+        );
+    }
+}
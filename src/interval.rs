@@ -0,0 +1,233 @@
+//! An async interval ticking at the times of a cron, like `tokio::time::Interval`.
+
+use crate::clock::{Clock, TokioClock};
+use crate::Cron;
+use chrono::{DateTime, Utc};
+use core::future::{poll_fn, Future};
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use futures_core::Stream;
+use tokio::time::Sleep;
+
+/// What a [`CronInterval`] does when ticks are missed, because the task calling
+/// [`tick`] was busy past the tick's time.
+///
+/// [`CronInterval`]: struct.CronInterval.html
+/// [`tick`]: struct.CronInterval.html#method.tick
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MissedTickBehavior {
+    /// Missed ticks fire immediately, one after another, until the interval caught up.
+    #[default]
+    Burst,
+    /// The late tick fires immediately, and the following tick is the first cron time at
+    /// least as far after now as it was after the late tick.
+    Delay,
+    /// The late tick fires immediately, and the following tick is the next cron time after
+    /// now, skipping the other missed ticks.
+    Skip,
+}
+
+/// An interval ticking at the times of a cron.
+///
+/// Time is measured with tokio's clock from the wall clock time the interval was created at,
+/// so the interval follows `tokio::time::pause` and `tokio::time::advance` in tests.
+///
+/// # Example
+/// ```
+/// use cron::interval::CronInterval;
+/// use cron::Cron;
+/// use chrono::prelude::*;
+///
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// tokio::time::pause();
+///
+/// let cron = "*/10 * * * * ? *".parse::<Cron>().unwrap();
+/// let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 5).unwrap();
+/// let mut interval = CronInterval::starting_at(cron, start);
+///
+/// assert_eq!(interval.tick().await, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 10).single());
+/// assert_eq!(interval.tick().await, Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 20).single());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct CronInterval {
+    cron: Cron,
+    clock: TokioClock,
+    /// The time of the next tick, or `None` if the cron has no more times.
+    next: Option<DateTime<Utc>>,
+    missed_tick_behavior: MissedTickBehavior,
+    sleep: Pin<Box<Sleep>>,
+}
+
+impl CronInterval {
+    /// Creates an interval ticking at the cron's times after now.
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn new(cron: Cron) -> Self {
+        Self::starting_at(cron, Utc::now())
+    }
+
+    /// Creates an interval whose clock starts at the given time, ticking at the cron's times
+    /// after it.
+    ///
+    /// Must be called within a tokio runtime.
+    pub fn starting_at(cron: Cron, start: DateTime<Utc>) -> Self {
+        let clock = TokioClock::starting_at(start);
+        let next = cron.next_after(start);
+        let mut interval = CronInterval {
+            cron,
+            clock,
+            next,
+            missed_tick_behavior: MissedTickBehavior::default(),
+            sleep: Box::pin(tokio::time::sleep_until(clock.instant_at(start))),
+        };
+        interval.reset_sleep();
+        interval
+    }
+
+    /// Returns the interval's cron.
+    pub fn cron(&self) -> &Cron {
+        &self.cron
+    }
+
+    /// Returns what the interval does when ticks are missed.
+    pub fn missed_tick_behavior(&self) -> MissedTickBehavior {
+        self.missed_tick_behavior
+    }
+
+    /// Sets what the interval does when ticks are missed.
+    pub fn set_missed_tick_behavior(&mut self, behavior: MissedTickBehavior) {
+        self.missed_tick_behavior = behavior;
+    }
+
+    /// Returns the current time on the interval's clock.
+    pub fn now(&self) -> DateTime<Utc> {
+        self.clock.now()
+    }
+
+    /// Returns the time of the next tick, or `None` if the cron has no more times.
+    pub fn next_tick(&self) -> Option<DateTime<Utc>> {
+        self.next
+    }
+
+    /// Waits until the next tick and returns its scheduled time, or returns `None` if the cron
+    /// has no more times.
+    pub async fn tick(&mut self) -> Option<DateTime<Utc>> {
+        poll_fn(|cx| self.poll_tick(cx)).await
+    }
+
+    /// Polls for the next tick, returning its scheduled time once it's due, or `None` if the
+    /// cron has no more times.
+    pub fn poll_tick(&mut self, cx: &mut Context<'_>) -> Poll<Option<DateTime<Utc>>> {
+        let next = match self.next {
+            Some(next) => next,
+            None => return Poll::Ready(None),
+        };
+        if self.sleep.as_mut().poll(cx).is_pending() {
+            return Poll::Pending;
+        }
+
+        let now = self.now();
+        self.next = match self.missed_tick_behavior {
+            _ if now < next + chrono::Duration::seconds(1) => self.cron.next_after(next),
+            MissedTickBehavior::Burst => self.cron.next_after(next),
+            MissedTickBehavior::Delay => self
+                .cron
+                .next_after(next)
+                .and_then(|following| self.cron.next_from(now + (following - next))),
+            MissedTickBehavior::Skip => self.cron.next_after(now),
+        };
+        self.reset_sleep();
+
+        Poll::Ready(Some(next))
+    }
+
+    fn reset_sleep(&mut self) {
+        if let Some(next) = self.next {
+            self.sleep.as_mut().reset(self.clock.instant_at(next));
+        }
+    }
+}
+
+impl Stream for CronInterval {
+    type Item = DateTime<Utc>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_tick(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    fn at(second: u32) -> Option<DateTime<Utc>> {
+        Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, second).single()
+    }
+
+    fn interval(behavior: MissedTickBehavior) -> CronInterval {
+        let cron = "*/10 * * * * ? *".parse::<Cron>().unwrap();
+        let mut interval = CronInterval::starting_at(cron, at(0).unwrap());
+        interval.set_missed_tick_behavior(behavior);
+        interval
+    }
+
+    /// Ticks once, then misses the ticks at 20 and 30 seconds.
+    async fn tick_then_miss(interval: &mut CronInterval) {
+        assert_eq!(interval.tick().await, at(10));
+        tokio::time::advance(Duration::from_secs(25)).await;
+        assert_eq!(interval.now(), at(35).unwrap());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn ticks_sleep_until_cron_times() {
+        let mut interval = interval(MissedTickBehavior::Burst);
+        let start = Instant::now();
+        assert_eq!(interval.tick().await, at(10));
+        assert_eq!(interval.tick().await, at(20));
+        assert_eq!(start.elapsed(), Duration::from_secs(20));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn missed_ticks_burst() {
+        let mut interval = interval(MissedTickBehavior::Burst);
+        tick_then_miss(&mut interval).await;
+        assert_eq!(interval.tick().await, at(20));
+        assert_eq!(interval.tick().await, at(30));
+        assert_eq!(interval.now(), at(35).unwrap());
+        assert_eq!(interval.tick().await, at(40));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn missed_ticks_delay() {
+        let mut interval = interval(MissedTickBehavior::Delay);
+        tick_then_miss(&mut interval).await;
+        assert_eq!(interval.tick().await, at(20));
+        // the next tick is at least 10 seconds after the late tick
+        assert_eq!(interval.tick().await, at(50));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn missed_ticks_skip() {
+        let mut interval = interval(MissedTickBehavior::Skip);
+        tick_then_miss(&mut interval).await;
+        assert_eq!(interval.tick().await, at(20));
+        assert_eq!(interval.tick().await, at(40));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn stream_ends_with_the_cron() {
+        use core::future::poll_fn;
+
+        let cron = "0 0 0 1 1 ? 2024-2025".parse::<Cron>().unwrap();
+        let mut interval = CronInterval::starting_at(cron, at(0).unwrap());
+        let next = poll_fn(|cx| Pin::new(&mut interval).poll_next(cx)).await;
+        assert_eq!(next, Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).single());
+        let next = poll_fn(|cx| Pin::new(&mut interval).poll_next(cx)).await;
+        assert_eq!(next, None);
+    }
+}
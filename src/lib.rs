@@ -2,6 +2,12 @@
 
 extern crate alloc;
 
+pub mod calendar;
+pub mod clock;
+pub mod compose;
+pub mod crontab;
+pub mod describe;
+pub mod interval;
 pub mod parse;
 pub mod scheduler;
 
@@ -14,7 +20,8 @@ use core::ops::{Bound, RangeBounds};
 use core::str::FromStr;
 use std::collections::BTreeSet;
 
-use self::parse::{CronExpr, ExprValue, OrsExpr};
+use self::calendar::Calendar;
+use self::parse::{CronExpr, DayMatch, ExprValue, OrsExpr, ParseOptions, ValueOutOfRangeError};
 
 pub(crate) mod internal {
     pub trait Sealed {}
@@ -103,7 +110,7 @@ impl TimePattern for DaysOfWeek {
     }
 }
 impl DaysOfWeek {
-    const BITS: u32 = 8;
+    const BITS: u32 = u32::BITS;
     const DAY_BITS: u32 = 0b0111_1111;
     const ONE_DAY_BITS: u32 = 0b0000_0111;
     const UPPER_BIT_BOUND: u32 = Self::DAY_BITS.trailing_ones() as u32;
@@ -113,8 +120,9 @@ impl DaysOfWeek {
         self.0
     }
 
-    fn is_star(&self) -> bool {
-        matches!(self.kind(), DaysOfWeekKind::Star)
+    /// Returns whether this expression restricts the matching days, meaning it isn't a '*' or '?'.
+    fn is_restricted(&self) -> bool {
+        !matches!(self.kind(), DaysOfWeekKind::Star | DaysOfWeekKind::Any)
     }
 
     #[inline]
@@ -220,20 +228,20 @@ impl DaysOfWeek {
                     // ... 0   1   1   1   1   1   1   0
                     bits = (bits >> start) << start;
                     // remove the start bits in the same way, shift the bits
-                    // to the left by the number of bits in the integer (8) minus
-                    // the end value (5) minus 1 (8 - 5 - 1 = 2).
+                    // to the left by the number of bits in the integer (32) minus
+                    // the end value (5) minus 1 (32 - 5 - 1 = 26).
                     // if we had a value that took up the whole bit map with a range
                     // that reached the max value, this operation would result in -1.
                     // In that case, we'd floor to 0 and not shift at all. but because
                     // it's the max value, we don't actually need to shift to truncate at
                     // all. so we can just skip this in that case.
                     //
-                    // shift left by 2
+                    // shift left by 26
                     // truncated
                     // (OOB)   | ALL SAT FRI THU WED TUE MON SUN
                     // 0   1   | 1   1   1   1   1   0   0   0
                     //
-                    // shift right by 2
+                    // shift right by 26
                     //
                     // ... ALL SAT FRI THU WED TUE MON SUN
                     // ... 0   0   1   1   1   1   1   0
@@ -266,7 +274,7 @@ impl DaysOfWeek {
                     // ... 0   1   1   1   1   1   1   1
                     let mut top_bits = Self::DAY_BITS;
                     // to remove the bottom bits, shift the top bits to the right
-                    // by the start value (6) minus one (5), then shift back.
+                    // by the zero based start value (5), then shift back.
                     //
                     // shift right by 5
                     //                                 truncated
@@ -277,7 +285,7 @@ impl DaysOfWeek {
                     //
                     // ... ALL SAT FRI THU WED TUE MON SUN
                     // ... 0   1   1   0   0   0   0   0
-                    let start = u32::from(start) - 1;
+                    let start = u32::from(start);
                     top_bits = (top_bits >> start) << start;
 
                     // make a separate mask
@@ -286,16 +294,16 @@ impl DaysOfWeek {
                     // ... 0   1   1   1   1   1   1   1
                     let mut bottom_bits = Self::DAY_BITS;
                     // to remove the top bits, shift the top bits to the left
-                    // by the number of bits in the integer (8) minus the end
-                    // value (0) plus one (8 - 0 + 1 = 7)
+                    // by the number of bits in the integer (32) minus the end
+                    // value (0) plus one (32 - (0 + 1) = 31)
                     //
-                    // shift left by 7
+                    // shift left by 31
                     // truncated
                     // ... (OOB)  | Out of mask bounds  ...
                     // ... 1   1  | 1   0   0   0   0   ...
                     //
                     //
-                    // shift right by 7
+                    // shift right by 31
                     //
                     // ... ALL SAT FRI THU WED TUE MON SUN
                     // ... 0   0   0   0   0   0   0   1
@@ -392,7 +400,7 @@ impl Seconds {
 
                     self.0 |= bits;
                 } else {
-                    let start = u32::from(start) - 1;
+                    let start = u32::from(start);
                     let end = u32::from(end) + 1;
 
                     let top_bits = (Self::ALL >> start) << start;
@@ -495,7 +503,7 @@ impl Minutes {
 
                     self.0 |= bits;
                 } else {
-                    let start = u32::from(start) - 1;
+                    let start = u32::from(start);
                     let end = u32::from(end) + 1;
 
                     let top_bits = (Self::ALL >> start) << start;
@@ -597,7 +605,7 @@ impl Hours {
 
                     self.0 |= bits;
                 } else {
-                    let start = u32::from(start) - 1;
+                    let start = u32::from(start);
                     let end = u32::from(end) + 1;
 
                     let top_bits = (Self::ALL >> start) << start;
@@ -643,6 +651,8 @@ enum DaysOfMonthKind {
     Last,
     Weekday,
     LastWeekday,
+    BusinessDay,
+    LastBusinessDay,
     Any,
 }
 
@@ -653,7 +663,7 @@ impl TimePattern for DaysOfMonth {
     type Expr = parse::DayOfMonthExpr;
 
     fn compile(expr: Self::Expr) -> Self {
-        use parse::{DayOfMonthExpr, Last};
+        use parse::{BusinessDay, DayOfMonthExpr, Last};
         match expr {
             DayOfMonthExpr::All => Self(DaysOfMonthKind::Star, 0),
             DayOfMonthExpr::Last(Last::Day) => Self(DaysOfMonthKind::Last, 0),
@@ -667,6 +677,12 @@ impl TimePattern for DaysOfMonth {
             DayOfMonthExpr::ClosestWeekday(day) => {
                 Self(DaysOfMonthKind::Weekday, (u32::from(day) + 1) as u32)
             }
+            DayOfMonthExpr::BusinessDay(BusinessDay::Nth(nth)) => {
+                Self(DaysOfMonthKind::BusinessDay, u32::from(nth))
+            }
+            DayOfMonthExpr::BusinessDay(BusinessDay::NthLast(nth)) => {
+                Self(DaysOfMonthKind::LastBusinessDay, u32::from(nth))
+            }
             DayOfMonthExpr::Many(exprs) => Self(
                 DaysOfMonthKind::Pattern,
                 exprs.into_iter().fold(0, Self::add_ors),
@@ -677,7 +693,7 @@ impl TimePattern for DaysOfMonth {
 
     #[inline]
     fn contains(&self, dt: DateTime<Utc>) -> bool {
-        self.contains_date(dt.date())
+        self.contains_date(dt.date(), Weekend::default(), &Calendar::new())
     }
 }
 impl DaysOfMonth {
@@ -699,8 +715,9 @@ impl DaysOfMonth {
         )
     }
 
-    fn is_star(&self) -> bool {
-        matches!(self.kind(), DaysOfMonthKind::Star)
+    /// Returns whether this expression restricts the matching days, meaning it isn't a '*' or '?'.
+    fn is_restricted(&self) -> bool {
+        !matches!(self.kind(), DaysOfMonthKind::Star | DaysOfMonthKind::Any)
     }
 
     /// Returns the one day set in this expression. Used to get last day offsets and the day
@@ -726,10 +743,7 @@ impl DaysOfMonth {
     }
 
     #[inline]
-    fn contains_date(&self, date: Date<Utc>) -> bool {
-        let is_weekend = |weekday| matches!(weekday, Weekday::Sat | Weekday::Sun);
-        let is_weekday = |weekday| !is_weekend(weekday);
-
+    fn contains_date(&self, date: Date<Utc>, weekend: Weekend, holidays: &Calendar) -> bool {
         let days_in_month = days_in_month(date);
         let day = date.day();
 
@@ -750,39 +764,77 @@ impl DaysOfMonth {
                 // resulting in underflow.
                 day + offset == days_in_month
             }
-            Self(DaysOfMonthKind::LastWeekday, 0) => {
-                // 'LW'
-                let weekday = date.weekday();
-                (is_weekday(weekday) && day == days_in_month)
-                    || (weekday == Weekday::Fri && days_in_month - day < 3)
+            Self(DaysOfMonthKind::LastWeekday, _) | Self(DaysOfMonthKind::Weekday, _) => {
+                // 'LW', 'L-3W' or '3W'
+                self.closest_weekday(date, weekend) == Some(day)
             }
-            &Self(DaysOfMonthKind::LastWeekday, offset) => {
-                // 'L' with an offset with the nearest weekday.
-                // Example: 'L-3W'
-                let weekday = date.weekday();
-                let day_offsetted = day + offset;
-                (is_weekday(weekday) && day_offsetted == days_in_month)
-                    // don't check for weekend month ends since we're always offset by one
-                    // at least, so our "end" can't be on a weekend ending month
-                    // but do check if the month starts with a weekend and this is that weekend's
-                    // Saturday or Sunday
-                    || (weekday == Weekday::Mon && day_offsetted - days_in_month < 3)
-                    || (weekday == Weekday::Fri && day_offsetted + 1 == days_in_month)
-            }
-            &Self(DaysOfMonthKind::Weekday, expected_day) => {
-                let weekday = date.weekday();
-                (is_weekday(weekday) && day == expected_day)
-                    || (weekday == Weekday::Mon && day - 1 == expected_day)
-                    // check for 1W on months where the 1st is Saturday and the 2nd is Sunday
-                    || (weekday == Weekday::Mon && day == 3 && expected_day == 1)
-                    || (weekday == Weekday::Fri && day + 1 == expected_day)
-                    // check for 31W, 30W, 29W, 28W where they're the last day of the month and are on Sunday
-                    || (weekday == Weekday::Fri && day + 2 == expected_day && expected_day == days_in_month)
+            Self(DaysOfMonthKind::BusinessDay, _) | Self(DaysOfMonthKind::LastBusinessDay, _) => {
+                // '3BD' or 'BD-2'
+                self.business_day(date, weekend, holidays) == Some(day)
             }
             _ => true,
         }
     }
 
+    /// Resolves a 'W' or 'LW' expression into the day of the month (1-31) it matches in the
+    /// month of the given date, or none if the expression never matches in that month.
+    ///
+    /// The closest weekday is searched for in both directions, preferring the earlier day if
+    /// two days are equally close. The search never leaves the month, so with a Saturday and
+    /// Sunday weekend, '1W' on a Saturday resolves to Monday the 3rd.
+    fn closest_weekday(&self, date: Date<Utc>, weekend: Weekend) -> Option<u32> {
+        let days_in_month = days_in_month(date);
+        let expected_day = match self.kind() {
+            DaysOfMonthKind::LastWeekday => days_in_month.checked_sub(self.one_value())?,
+            DaysOfMonthKind::Weekday => self.one_value(),
+            _ => return None,
+        };
+        if expected_day == 0 || expected_day > days_in_month {
+            return None;
+        }
+
+        let first_weekday = date.with_day(1)?.weekday();
+        let is_weekday = |day: u32| {
+            let weekday = (first_weekday.num_days_from_sunday() + day - 1) % 7;
+            !weekend.contains(DaysOfWeek::byte_to_weekday(weekday))
+        };
+
+        if is_weekday(expected_day) {
+            return Some(expected_day);
+        }
+
+        (1..7).find_map(|distance| {
+            let before = expected_day
+                .checked_sub(distance)
+                .filter(|&day| day >= 1 && is_weekday(day));
+            let after = Some(expected_day + distance)
+                .filter(|&day| day <= days_in_month && is_weekday(day));
+            before.or(after)
+        })
+    }
+
+    /// Resolves a 'BD' expression into the day of the month (1-31) it matches in the month of the
+    /// given date, or none if the month doesn't have enough business days.
+    fn business_day(&self, date: Date<Utc>, weekend: Weekend, holidays: &Calendar) -> Option<u32> {
+        let first = date.with_day(1)?.naive_utc();
+        let mut business_days = first
+            .iter_days()
+            .take(days_in_month(date) as usize)
+            .filter(|day| !weekend.contains(day.weekday()) && !holidays.is_holiday(*day))
+            .map(|day| day.day());
+        let nth = self.one_value() as usize;
+
+        match self.kind() {
+            DaysOfMonthKind::BusinessDay => business_days.nth(nth - 1),
+            DaysOfMonthKind::LastBusinessDay => business_days
+                .collect::<Vec<_>>()
+                .into_iter()
+                .rev()
+                .nth(nth - 1),
+            _ => None,
+        }
+    }
+
     #[inline]
     fn value_pattern<T>(value: T) -> u32
     where
@@ -815,7 +867,7 @@ impl DaysOfMonth {
 
                     pattern |= bits;
                 } else {
-                    let start = u32::from(start) - 1;
+                    let start = u32::from(start);
                     let end = u32::from(end) + 1;
 
                     let top_bits = (Self::DAY_BITS >> start) << start;
@@ -875,7 +927,7 @@ impl TimePattern for Months {
     }
 }
 impl Months {
-    const BITS: u32 = 16;
+    const BITS: u32 = u32::BITS;
     const ALL: u32 = 0x0FFF;
     const UPPER_BIT_BOUND: u32 = Self::ALL.trailing_ones() as u32;
 
@@ -915,7 +967,7 @@ impl Months {
 
                     self.0 |= bits;
                 } else {
-                    let start = u32::from(start) - 1;
+                    let start = u32::from(start);
                     let end = u32::from(end) + 1;
 
                     let top_bits = (Self::ALL >> start) << start;
@@ -964,7 +1016,7 @@ impl TimePattern for Years {
         match expr {
             parse::Expr::All => {
                 let mut btree_set = BTreeSet::<u32>::new();
-                let range = parse::Year::MIN..parse::Year::MAX;
+                let range = parse::Year::MIN..=parse::Year::MAX;
                 let range_iter = range.into_iter();
                 for i in range_iter {
                     btree_set.insert(i);
@@ -1005,13 +1057,13 @@ impl Years {
                 let end = u32::from(end);
 
                 if start < end {
-                    let range = start..end;
+                    let range = start..=end;
                     for i in range {
                         self.0.insert(i);
                     }
                 } else {
-                    let left_range = parse::Year::MIN..start;
-                    let right_range = end..parse::Year::MAX;
+                    let left_range = parse::Year::MIN..=end;
+                    let right_range = start..=parse::Year::MAX;
 
                     for i in left_range {
                         self.0.insert(i);
@@ -1033,8 +1085,8 @@ impl Years {
                         self.0.insert(i);
                     }
                 } else {
-                    let back = start..=parse::Month::MAX;
-                    let front = parse::Month::MIN..=end;
+                    let back = start..=parse::Year::MAX;
+                    let front = parse::Year::MIN..=end;
                     let range = back.chain(front).step_by(u32::from(step) as usize);
                     for i in range {
                         self.0.insert(i);
@@ -1047,6 +1099,169 @@ impl Years {
     }
 }
 
+/// A set of weeks a cron value is limited to. Cron expressions can't express week based schedules
+/// like "every other Monday", so this can be added onto a cron value with [`Cron::with_weeks`].
+///
+/// [`Cron::with_weeks`]: struct.Cron.html#method.with_weeks
+///
+/// # Example
+/// ```
+/// use cron::{Cron, Weeks};
+/// use chrono::prelude::*;
+///
+/// // every other Monday at 09:00, starting with the week of January 2nd 2023
+/// let anchor = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+/// let cron = "0 0 9 ? * MON *"
+///     .parse::<Cron>()
+///     .unwrap()
+///     .with_weeks(Weeks::every(2, anchor).unwrap());
+///
+/// assert!(cron.contains(Utc.with_ymd_and_hms(2023, 1, 16, 9, 0, 0).unwrap()));
+/// assert!(!cron.contains(Utc.with_ymd_and_hms(2023, 1, 9, 9, 0, 0).unwrap()));
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
+pub struct Weeks(WeeksKind);
+
+#[derive(Debug, Default, PartialEq, Eq, Hash, Clone)]
+enum WeeksKind {
+    /// Every week
+    #[default]
+    All,
+    /// A bit-mask of ISO 8601 weeks of the year, 1-53
+    Iso(u64),
+    /// Every nth week counted from the Monday starting the week of the anchor
+    Every { monday: NaiveDate, n: u32 },
+}
+
+impl Weeks {
+    const ISO_MAX: u32 = 53;
+    /// How long after the first day skipped for its week a search gives up, in months. The
+    /// Gregorian calendar repeats every 400 years, so the weeks fall on the same days again.
+    const GIVE_UP_MONTHS: u32 = 400 * 12;
+    const ODD_BITS: u64 = 0x002A_AAAA_AAAA_AAAA;
+    const EVEN_BITS: u64 = 0x0015_5555_5555_5554;
+
+    /// Matches every week. This is the default for all cron values.
+    pub fn all() -> Self {
+        Self(WeeksKind::All)
+    }
+
+    /// Matches the given ISO 8601 weeks of the year, 1-53. Returns an error if a week is out
+    /// of range or no week is given.
+    pub fn iso<I>(weeks: I) -> Result<Self, ValueOutOfRangeError>
+    where
+        I: IntoIterator<Item = u32>,
+    {
+        let mut bits = 0u64;
+        for week in weeks {
+            if week == 0 || week > Self::ISO_MAX {
+                return Err(ValueOutOfRangeError);
+            }
+            bits |= 1 << week;
+        }
+        if bits == 0 {
+            return Err(ValueOutOfRangeError);
+        }
+        Ok(Self(WeeksKind::Iso(bits)))
+    }
+
+    /// Matches odd ISO 8601 weeks of the year (1, 3, 5, ...). Since a year can end with week 53,
+    /// two odd weeks can follow each other at the start of a year.
+    pub fn odd() -> Self {
+        Self(WeeksKind::Iso(Self::ODD_BITS))
+    }
+
+    /// Matches even ISO 8601 weeks of the year (2, 4, 6, ...).
+    pub fn even() -> Self {
+        Self(WeeksKind::Iso(Self::EVEN_BITS))
+    }
+
+    /// Matches every `n`th week, starting with the week containing the anchor date. Weeks start
+    /// on Monday. Returns `None` if `n` is zero.
+    pub fn every(n: u32, anchor: NaiveDate) -> Option<Self> {
+        if n == 0 {
+            return None;
+        }
+
+        let monday = week_start(anchor)?;
+        Some(Self(WeeksKind::Every { monday, n }))
+    }
+
+    /// Returns whether the week of the given date matches.
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        match self.0 {
+            WeeksKind::All => true,
+            WeeksKind::Iso(bits) => bits & (1 << date.iso_week().week()) != 0,
+            WeeksKind::Every { monday, n } => match week_start(date) {
+                Some(start) => ((start - monday).num_weeks()).rem_euclid(i64::from(n)) == 0,
+                None => false,
+            },
+        }
+    }
+
+    fn is_all(&self) -> bool {
+        matches!(self.0, WeeksKind::All)
+    }
+}
+
+/// Returns the Monday starting the week of the given date.
+#[inline]
+fn week_start(date: NaiveDate) -> Option<NaiveDate> {
+    date.checked_sub_signed(Duration::days(i64::from(
+        date.weekday().num_days_from_monday(),
+    )))
+}
+
+/// The days of the week treated as the weekend when resolving the closest weekday of a 'W' or
+/// 'LW' day of the month expression. By default, the weekend is Saturday and Sunday.
+///
+/// # Example
+/// ```
+/// use cron::{Cron, Weekend};
+/// use chrono::prelude::*;
+///
+/// let cron = "0 0 0 15W * ? *".parse::<Cron>().unwrap();
+/// // June 15th 2023 is a Thursday
+/// let date = Utc.with_ymd_and_hms(2023, 6, 15, 0, 0, 0).unwrap();
+/// assert!(cron.contains(date));
+///
+/// // September 15th 2023 is a Friday, so the closest weekday is Thursday the 14th
+/// let cron = cron.with_weekend(Weekend::FRIDAY_SATURDAY);
+/// let date = Utc.with_ymd_and_hms(2023, 9, 14, 0, 0, 0).unwrap();
+/// assert!(cron.contains(date));
+/// ```
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+pub struct Weekend(u8);
+
+impl Weekend {
+    /// A Saturday and Sunday weekend
+    pub const SATURDAY_SUNDAY: Self = Self(0b0100_0001);
+    /// A Friday and Saturday weekend
+    pub const FRIDAY_SATURDAY: Self = Self(0b0110_0000);
+
+    /// Creates a weekend from the given days.
+    pub fn new<I>(days: I) -> Self
+    where
+        I: IntoIterator<Item = Weekday>,
+    {
+        Self(
+            days.into_iter()
+                .fold(0, |bits, day| bits | (1 << day.num_days_from_sunday())),
+        )
+    }
+
+    /// Returns whether the given day is part of the weekend.
+    pub fn contains(&self, day: Weekday) -> bool {
+        self.0 & (1 << day.num_days_from_sunday()) != 0
+    }
+}
+
+impl Default for Weekend {
+    fn default() -> Self {
+        Self::SATURDAY_SUNDAY
+    }
+}
+
 /// A cron value. This can be used to iterate over all future matching times or quickly check if
 /// a given time matches.
 ///
@@ -1081,6 +1296,12 @@ pub struct Cron {
     months: Months,
     dow: DaysOfWeek,
     years: Years,
+    day_match: DayMatch,
+    weeks: Weeks,
+    weekend: Weekend,
+    holidays: Calendar,
+    /// The other members of a multi-cron, whose times are matched as well
+    others: Vec<Cron>,
 }
 
 impl FromStr for Cron {
@@ -1093,7 +1314,7 @@ impl FromStr for Cron {
         // of an integer represent the minutes/hours/days/months/weekdays
         // in a cron expression. It might be compressable further but I
         // doubt we'll need to do that.
-        s.parse().map(Cron::new)
+        Cron::parse_with(ParseOptions::default(), s)
     }
 }
 
@@ -1108,9 +1329,125 @@ impl Cron {
             months: TimePattern::compile(expr.months),
             dow: TimePattern::compile(expr.dows),
             years: TimePattern::compile(expr.years),
+            day_match: expr.day_match,
+            weeks: Weeks::all(),
+            weekend: Weekend::default(),
+            holidays: Calendar::new(),
+            others: Vec::new(),
         }
     }
 
+    /// Combines two crons into a multi-cron matching the times of either. Settings made
+    /// before combining stay as they were set on each, while [`with_weekend`],
+    /// [`with_holidays`] and [`with_weeks`] called on the multi-cron apply to all of them.
+    ///
+    /// # Example
+    /// ```
+    /// use cron::Cron;
+    /// use chrono::prelude::*;
+    ///
+    /// let weekdays = "0 0 9 ? * MON-FRI *".parse::<Cron>().unwrap();
+    /// let weekends = "0 0 11 ? * SAT,SUN *".parse::<Cron>().unwrap();
+    /// let cron = weekdays.or(weekends);
+    ///
+    /// // Saturday the 6th
+    /// let friday = Utc.with_ymd_and_hms(2024, 1, 5, 9, 0, 0).unwrap();
+    /// assert_eq!(cron.next_after(friday), Utc.with_ymd_and_hms(2024, 1, 6, 11, 0, 0).single());
+    /// ```
+    ///
+    /// [`with_weekend`]: #method.with_weekend
+    /// [`with_holidays`]: #method.with_holidays
+    /// [`with_weeks`]: #method.with_weeks
+    pub fn or(mut self, mut other: Cron) -> Self {
+        let others = core::mem::take(&mut other.others);
+        self.others.push(other);
+        self.others.extend(others);
+        self
+    }
+
+    /// Sets the days treated as the weekend when resolving 'W', 'LW' and 'BD' expressions.
+    /// On a multi-cron, this replaces the weekend of every member.
+    pub fn with_weekend(mut self, weekend: Weekend) -> Self {
+        self.weekend = weekend;
+        self.others = self
+            .others
+            .into_iter()
+            .map(|other| other.with_weekend(weekend))
+            .collect();
+        self
+    }
+
+    /// Sets the holidays skipped when counting business days for 'BD' expressions. Only the
+    /// calendar's single and annual dates are used. On a multi-cron, this replaces the holidays
+    /// of every member.
+    ///
+    /// # Example
+    /// ```
+    /// use cron::Cron;
+    /// use cron::calendar::Calendar;
+    /// use chrono::prelude::*;
+    ///
+    /// // the 3rd business day of each month
+    /// let cron = "0 0 9 3BD * ? *".parse::<Cron>().unwrap();
+    /// let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+    /// assert_eq!(cron.next_after(start), Utc.with_ymd_and_hms(2024, 1, 3, 9, 0, 0).single());
+    ///
+    /// // new year's day isn't a business day
+    /// let cron = cron.with_holidays("01-01".parse::<Calendar>().unwrap());
+    /// assert_eq!(cron.next_after(start), Utc.with_ymd_and_hms(2024, 1, 4, 9, 0, 0).single());
+    /// ```
+    pub fn with_holidays(mut self, holidays: Calendar) -> Self {
+        self.others = self
+            .others
+            .into_iter()
+            .map(|other| other.with_holidays(holidays.clone()))
+            .collect();
+        self.holidays = holidays;
+        self
+    }
+
+    /// Limits this cron value to the given set of weeks. On a multi-cron, this replaces the
+    /// weeks of every member.
+    ///
+    /// Weeks the cron's days never fall in, like ISO week 1 for a cron running in July, make
+    /// it never match. Searches then give up after 400 years, which can take tens of
+    /// milliseconds.
+    pub fn with_weeks(mut self, weeks: Weeks) -> Self {
+        self.weeks = weeks.clone();
+        self.others = self
+            .others
+            .into_iter()
+            .map(|other| other.with_weeks(weeks.clone()))
+            .collect();
+        self
+    }
+
+    /// Parses and compiles a cron expression using the given parse options.
+    ///
+    /// # Example
+    /// ```
+    /// use cron::Cron;
+    /// use cron::parse::{DayMatch, ParseOptions};
+    /// use chrono::prelude::*;
+    ///
+    /// let options = ParseOptions::new().both_days(DayMatch::And);
+    /// let cron = Cron::parse_with(options, "0 0 0 13 * FRI *").unwrap();
+    ///
+    /// // Friday the 13th
+    /// let date = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+    /// assert_eq!(
+    ///     cron.next_after(date),
+    ///     Some(Utc.with_ymd_and_hms(2023, 1, 13, 0, 0, 0).unwrap())
+    /// );
+    /// ```
+    pub fn parse_with(options: ParseOptions, s: &str) -> Result<Self, parse::CronParseError> {
+        let mut members = s
+            .split(['|', ';'])
+            .map(|member| CronExpr::parse_with(options, member.trim()).map(Cron::new));
+        let first = members.next().expect("split yields at least one item")?;
+        members.try_fold(first, |cron, member| Ok(cron.or(member?)))
+    }
+
     /// Returns whether this cron value will ever match any giving time.
     ///
     /// Some values can never match any given time. If an value matches
@@ -1140,7 +1477,6 @@ impl Cron {
                     offset => offset + 1,
                 }
             } else {
-                println!("{:?}", self.dom.first_set());
                 match self.dom.first_set() {
                     Some(_) => {}
                     None => return false,
@@ -1178,34 +1514,55 @@ impl Cron {
     /// ```
     #[inline]
     pub fn contains(&self, dt: DateTime<Utc>) -> bool {
+        self.contains_own(dt) || self.others.iter().any(|other| other.contains(dt))
+    }
+
+    /// Returns whether this cron value matches the given time, ignoring the other members of
+    /// a multi-cron.
+    #[inline]
+    fn contains_own(&self, dt: DateTime<Utc>) -> bool {
         let contains_minutes_hour_months = self.seconds.contains(dt)
             && self.minutes.contains(dt)
             && self.hours.contains(dt)
-            && self.months.contains(dt);
+            && self.months.contains(dt)
+            && self.years.contains(dt);
 
-        if !contains_minutes_hour_months {
+        if !contains_minutes_hour_months || !self.weeks.contains(dt.date_naive()) {
             return false;
         }
 
-        match (self.dom.is_star(), self.dow.is_star()) {
-            (true, true) => true,
-            (true, false) => self.dow.contains(dt),
-            (false, true) => self.dom.contains(dt),
-            (false, false) => self.dow.contains(dt) || self.dom.contains(dt),
-        }
+        self.contains_day(dt.date())
     }
 
     #[inline]
     fn contains_date(&self, date: Date<Utc>) -> bool {
-        if !self.years.contains_years(date) || !self.months.contains_month(date) {
+        if !self.years.contains_years(date)
+            || !self.months.contains_month(date)
+            || !self.weeks.contains(date.naive_utc())
+        {
             return false;
         }
 
-        match (self.dom.is_star(), self.dow.is_star()) {
-            (true, true) => true,
-            (true, false) => self.dow.contains_date(date),
-            (false, true) => self.dom.contains_date(date),
-            (false, false) => self.dow.contains_date(date) || self.dom.contains_date(date),
+        self.contains_day(date)
+    }
+
+    /// Returns whether the day of the month and day of the week parts match the given date.
+    #[inline]
+    fn contains_day(&self, date: Date<Utc>) -> bool {
+        match (self.dom.is_restricted(), self.dow.is_restricted()) {
+            (false, false) => true,
+            (false, true) => self.dow.contains_date(date),
+            (true, false) => self.dom.contains_date(date, self.weekend, &self.holidays),
+            (true, true) => match self.day_match {
+                DayMatch::Or => {
+                    self.dow.contains_date(date)
+                        || self.dom.contains_date(date, self.weekend, &self.holidays)
+                }
+                DayMatch::And => {
+                    self.dow.contains_date(date)
+                        && self.dom.contains_date(date, self.weekend, &self.holidays)
+                }
+            },
         }
     }
 
@@ -1287,16 +1644,16 @@ impl Cron {
         let front = match bounds.start_bound() {
             Bound::Unbounded => Some(chrono::MIN_DATETIME),
             Bound::Included(start) => Some(*start),
-            Bound::Excluded(start) => next_minute(*start),
+            Bound::Excluded(start) => next_second(*start),
         }
-        .map(minute_floor);
+        .map(second_floor);
 
         let back = match bounds.end_bound() {
             Bound::Unbounded => Some(chrono::MAX_DATETIME),
             Bound::Included(end) => Some(*end),
-            Bound::Excluded(end) => previous_minute(*end),
+            Bound::Excluded(end) => previous_second(*end),
         }
-        .map(minute_floor);
+        .map(second_floor);
 
         CronTimesIter {
             cron: self,
@@ -1347,9 +1704,20 @@ impl Cron {
         }
     }
 
+    /// Finds the next (current inclusive) matching date time of any member of the cron in the
+    /// future within the specified date time bound, or none if the search exceeds the bound.
+    fn find_next(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let own = self.find_next_own(start, end);
+        self.others
+            .iter()
+            .filter_map(|other| other.find_next(start, end))
+            .chain(own)
+            .min()
+    }
+
     /// Finds the next (current inclusive) matching date time in the future within the specified
     /// date time bound, or none if the search exceeds the bound.
-    fn find_next(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    fn find_next_own(&self, start: DateTime<Utc>, end: DateTime<Utc>) -> Option<DateTime<Utc>> {
         if self.contains_date(start.date()) {
             match self.find_next_time(start.time(), time_bound_for_date(start.date(), end)) {
                 Ok(Some(next_time)) => {
@@ -1363,12 +1731,30 @@ impl Cron {
 
         let midnight = NaiveTime::from_hms(0, 0, 0);
         let mut search_date = start.date().succ_opt().filter(|&t| t <= end.date())?;
+        let mut give_up = None;
 
         loop {
             match self.find_next_date(search_date, end.date()) {
+                Ok(Some(next_date))
+                    if !self.weeks.is_all() && !self.weeks.contains(next_date.naive_utc()) =>
+                {
+                    // the day matches, but its week doesn't. continue from the start of the
+                    // next week, unless the days seem to never fall in the weeks.
+                    let give_up = *give_up.get_or_insert_with(|| {
+                        next_date
+                            .naive_utc()
+                            .checked_add_months(chrono::Months::new(Weeks::GIVE_UP_MONTHS))
+                            .unwrap_or(NaiveDate::MAX)
+                    });
+                    if next_date.naive_utc() > give_up {
+                        return None;
+                    }
+                    let days_left = 7 - next_date.weekday().num_days_from_monday();
+                    search_date = next_date
+                        .checked_add_signed(Duration::days(i64::from(days_left)))
+                        .filter(|&d| d <= end.date())?;
+                }
                 Ok(Some(next_date)) => {
-                    println!("{:?}", next_date);
-
                     return match self.find_next_time(midnight, time_bound_for_date(next_date, end))
                     {
                         Ok(Some(next_time)) => next_date.and_time(next_time),
@@ -1376,26 +1762,18 @@ impl Cron {
                     };
                 }
                 Err(OutOfBound) => return None,
-                Ok(None) => {
-                    println!("aaaa");
-
-                    let next_year = self.find_next_year(search_date);
-
-                    println!("{:?}", next_year);
-
-                    match next_year {
-                        Some(d) => {
-                            if d > end.date() {
-                                return None;
-                            } else {
-                                search_date = d;
-                            }
-                        }
-                        None => {
+                Ok(None) => match self.find_next_year(search_date) {
+                    Some(d) => {
+                        if d > end.date() {
                             return None;
+                        } else {
+                            search_date = d;
                         }
                     }
-                }
+                    None => {
+                        return None;
+                    }
+                },
             }
         }
     }
@@ -1461,18 +1839,24 @@ impl Cron {
                     (Some(next_second), _) => return Ok(Some(next_second)),
                     (None, _) => {}
                 }
-
-                // contains minute but, the second doesn't match
-                let next_second = NaiveTime::from_hms_opt(start.hour(), start.minute() + 1, 0)
-                    .and_then(|time| self.find_next_minute(time))
-                    .and_then(|time| self.find_next_second(time));
-
-                match (next_second, end) {
-                    (Some(next_second), Some(end)) if next_second > end => return Err(OutOfBound),
-                    (Some(next_second), _) => return Ok(Some(next_second)),
-                    (None, _) => return Ok(None),
-                };
             }
+
+            // the minute or its remaining seconds don't match, try the later minutes of the hour
+            let next_minute = if self.minutes.contains_minute(start) {
+                start.minute() + 1
+            } else {
+                start.minute()
+            };
+            let next_second = NaiveTime::from_hms_opt(start.hour(), next_minute, 0)
+                .and_then(|time| self.find_next_minute(time))
+                .and_then(|time| self.find_next_second(time));
+
+            match (next_second, end) {
+                (Some(next_second), Some(end)) if next_second > end => return Err(OutOfBound),
+                (Some(next_second), _) => return Ok(Some(next_second)),
+                // no later minute in this hour matches, try the next hours
+                (None, _) => {}
+            };
         }
 
         let next_minute = NaiveTime::from_hms_opt(start.hour() + 1, 0, 0)
@@ -1490,11 +1874,11 @@ impl Cron {
     /// Gets the next matching (current inclusive) day of the month or day of the week that
     /// matches the cron expression. The returned matching day is a value 0-30.
     fn find_next_day(&self, start: Date<Utc>) -> Option<Date<Utc>> {
-        match (self.dom.is_star(), self.dow.is_star()) {
-            (true, true) => Some(start),
-            (true, false) => self.find_next_weekday(start),
-            (false, true) => self.find_next_day_of_month(start),
-            (false, false) => {
+        match (self.dom.is_restricted(), self.dow.is_restricted()) {
+            (false, false) => Some(start),
+            (false, true) => self.find_next_weekday(start),
+            (true, false) => self.find_next_day_of_month(start),
+            (true, true) if self.day_match == DayMatch::Or => {
                 let next_weekday = self.find_next_weekday(start);
                 let next_day = self.find_next_day_of_month(start);
                 match (next_day, next_weekday) {
@@ -1504,6 +1888,19 @@ impl Cron {
                     (None, None) => None,
                 }
             }
+            (true, true) => {
+                // both parts have to match the same day. keep moving the search forward to the
+                // later of the two candidates until they agree or we run out of days in the month.
+                let mut search = start;
+                loop {
+                    let next_day = self.find_next_day_of_month(search)?;
+                    let next_weekday = self.find_next_weekday(search)?;
+                    if next_day == next_weekday {
+                        return Some(next_day);
+                    }
+                    search = cmp::max(next_day, next_weekday);
+                }
+            }
         }
     }
 
@@ -1517,40 +1914,13 @@ impl Cron {
                 // 'L-3'
                 offset => start.with_day(days_in_month.checked_sub(offset as u32)?),
             },
-            DaysOfMonthKind::LastWeekday => match self.dom.one_value() {
-                // 'LW'
-                0 => {
-                    let next_date = start.with_day(days_in_month)?;
-                    match next_date.weekday() {
-                        Weekday::Sat => start.with_day(days_in_month - 1),
-                        Weekday::Sun => start.with_day(days_in_month - 2),
-                        _ => Some(next_date),
-                    }
-                }
-                // 'L-3W'
-                offset => {
-                    let expected_day = days_in_month.checked_sub(offset as u32)?;
-                    let next_date = start.with_day(expected_day)?;
-                    match next_date.weekday() {
-                        Weekday::Sat if expected_day == 1 => start.with_day(3),
-                        Weekday::Sat => start.with_day(expected_day - 1),
-                        Weekday::Sun => start.with_day(expected_day + 1),
-                        _ => Some(next_date),
-                    }
-                }
-            },
-            DaysOfMonthKind::Weekday => {
-                let expected_day = self.dom.one_value() as u32;
-                let new_date = start.with_day(expected_day)?;
-                match new_date.weekday() {
-                    Weekday::Sat if expected_day == 1 => start.with_day(3),
-                    Weekday::Sat => start.with_day(expected_day - 1),
-                    Weekday::Sun if expected_day == days_in_month => {
-                        start.with_day(days_in_month - 2)
-                    }
-                    Weekday::Sun => start.with_day(expected_day + 1),
-                    _ => Some(new_date),
-                }
+            DaysOfMonthKind::LastWeekday | DaysOfMonthKind::Weekday => {
+                // 'LW', 'L-3W' or '3W'
+                start.with_day(self.dom.closest_weekday(start, self.weekend)?)
+            }
+            DaysOfMonthKind::BusinessDay | DaysOfMonthKind::LastBusinessDay => {
+                // '3BD' or 'BD-2'
+                start.with_day(self.dom.business_day(start, self.weekend, &self.holidays)?)
             }
             _ => {
                 let map = self.dom.1 & DaysOfMonth::DAY_BITS;
@@ -1708,14 +2078,6 @@ impl Cron {
 
 struct OutOfBound;
 
-#[inline]
-fn minute_floor(dt: DateTime<Utc>) -> DateTime<Utc> {
-    dt.with_second(0)
-        .expect("zero is a valid second value")
-        .with_nanosecond(0)
-        .expect("zero is a valid nanosecond value")
-}
-
 #[inline]
 fn second_floor(dt: DateTime<Utc>) -> DateTime<Utc> {
     dt.with_nanosecond(0)
@@ -1723,13 +2085,8 @@ fn second_floor(dt: DateTime<Utc>) -> DateTime<Utc> {
 }
 
 #[inline]
-fn previous_minute(dt: DateTime<Utc>) -> Option<DateTime<Utc>> {
-    dt.checked_sub_signed(Duration::minutes(1))
-}
-
-#[inline]
-fn next_minute(dt: DateTime<Utc>) -> Option<DateTime<Utc>> {
-    dt.checked_add_signed(Duration::minutes(1))
+fn previous_second(dt: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    dt.checked_sub_signed(Duration::seconds(1))
 }
 
 fn next_second(dt: DateTime<Utc>) -> Option<DateTime<Utc>> {
@@ -1780,7 +2137,7 @@ impl Iterator for CronTimesIter {
     fn next(&mut self) -> Option<Self::Item> {
         if let Some((start, end)) = self.bounds {
             if let Some(next) = self.cron.find_next(start, end) {
-                self.bounds = next_minute(next).map(|new_start| (new_start, end));
+                self.bounds = next_second(next).map(|new_start| (new_start, end));
                 return Some(next);
             }
 
@@ -2192,6 +2549,339 @@ mod tests {
         );
     }
 
+    #[test]
+    fn parse_check_any_day_part_does_not_match_everything() {
+        let cron = "0 0 0 15 * ? *";
+
+        check_does_contain(cron, ["2023-01-15 00:00:00", "2023-02-15 00:00:00"]);
+        check_does_not_contain(cron, ["2023-01-14 00:00:00", "2023-01-16 00:00:00"]);
+    }
+
+    #[test]
+    fn parse_check_both_day_parts_or() {
+        let options = ParseOptions::new().both_days(DayMatch::Or);
+        let cron = Cron::parse_with(options, "0 0 0 13 * FRI *").unwrap();
+
+        for (month, day) in [(1, 6), (2, 13), (1, 13)] {
+            let date = Utc.with_ymd_and_hms(2023, month, day, 0, 0, 0).unwrap();
+            assert!(cron.contains(date), "{} should match", date);
+        }
+
+        assert!(!cron.contains(Utc.with_ymd_and_hms(2023, 1, 7, 0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn parse_check_both_day_parts_and() {
+        let options = ParseOptions::new().both_days(DayMatch::And);
+        let cron = Cron::parse_with(options, "0 0 0 13 * FRI *").unwrap();
+
+        for (month, day) in [(1, 6), (2, 13)] {
+            let date = Utc.with_ymd_and_hms(2023, month, day, 0, 0, 0).unwrap();
+            assert!(!cron.contains(date), "{} shouldn't match", date);
+        }
+
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        let times = cron
+            .iter_after(start)
+            .take(3)
+            .map(|time| time.format(FORMAT).to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            times,
+            [
+                "2023-01-13 00:00:00",
+                "2023-10-13 00:00:00",
+                "2024-09-13 00:00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_both_day_parts_is_rejected_by_default() {
+        assert!("0 0 0 13 * FRI *".parse::<Cron>().is_err());
+    }
+
+    fn format_next(cron: Cron, start: DateTime<Utc>, n: usize) -> Vec<String> {
+        cron.iter_after(start)
+            .take(n)
+            .map(|time| time.format(FORMAT).to_string())
+            .collect()
+    }
+
+    #[test]
+    fn weeks_every_other_monday() {
+        let anchor = NaiveDate::from_ymd_opt(2023, 1, 2).unwrap();
+        let cron = "0 0 9 ? * MON *"
+            .parse::<Cron>()
+            .unwrap()
+            .with_weeks(Weeks::every(2, anchor).unwrap());
+
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            format_next(cron, start, 3),
+            [
+                "2023-01-02 09:00:00",
+                "2023-01-16 09:00:00",
+                "2023-01-30 09:00:00"
+            ]
+        );
+    }
+
+    #[test]
+    fn weeks_iso_weeks_of_the_year() {
+        let cron = "0 0 9 ? * MON *"
+            .parse::<Cron>()
+            .unwrap()
+            .with_weeks(Weeks::iso([1, 3]).unwrap());
+
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            format_next(cron, start, 3),
+            [
+                "2024-01-01 09:00:00",
+                "2024-01-15 09:00:00",
+                // the first ISO week of 2025 starts in 2024
+                "2024-12-30 09:00:00"
+            ]
+        );
+
+        assert!(Weeks::iso([]).is_err());
+
+        // July is never in the first week of the year
+        let cron = "0 0 12 * 7 ? *"
+            .parse::<Cron>()
+            .unwrap()
+            .with_weeks(Weeks::iso([1]).unwrap());
+        assert_eq!(cron.next_after(start), None);
+    }
+
+    #[test]
+    fn weeks_parity() {
+        let cron = "0 0 0 * * ? *".parse::<Cron>().unwrap();
+        let odd = cron.clone().with_weeks(Weeks::odd());
+        let even = cron.with_weeks(Weeks::even());
+
+        // 2020 has 53 ISO weeks, so two odd weeks follow each other
+        for (date, is_odd) in [
+            (Utc.with_ymd_and_hms(2020, 12, 31, 0, 0, 0).unwrap(), true),
+            (Utc.with_ymd_and_hms(2021, 1, 4, 0, 0, 0).unwrap(), true),
+            (Utc.with_ymd_and_hms(2021, 1, 11, 0, 0, 0).unwrap(), false),
+        ] {
+            assert_eq!(odd.contains(date), is_odd, "{}", date);
+            assert_eq!(even.contains(date), !is_odd, "{}", date);
+        }
+
+        assert!(Weeks::iso([0]).is_err());
+        assert!(Weeks::iso([54]).is_err());
+    }
+
+    #[test]
+    fn closest_weekday_default_weekend() {
+        check_does_contain("0 0 0 1W 5 ? *", ["2021-05-03 00:00:00"]);
+        check_does_contain("0 0 0 LW 5 ? *", ["2020-05-29 00:00:00"]);
+        check_does_contain("0 0 0 31W 12 ? *", ["2023-12-29 00:00:00"]);
+        check_does_not_contain("0 0 0 31W 12 ? *", ["2023-12-31 00:00:00"]);
+    }
+
+    #[test]
+    fn closest_weekday_custom_weekend() {
+        let with_weekend = |cron: &str| {
+            cron.parse::<Cron>()
+                .unwrap()
+                .with_weekend(Weekend::FRIDAY_SATURDAY)
+        };
+        let start = Utc.with_ymd_and_hms(2023, 1, 1, 0, 0, 0).unwrap();
+
+        // Friday the 15th moves back to Thursday, Saturday the 16th moves on to Sunday
+        let cron = with_weekend("0 0 0 15W 9 ? *");
+        assert!(cron.contains(Utc.with_ymd_and_hms(2023, 9, 14, 0, 0, 0).unwrap()));
+        let cron = with_weekend("0 0 0 16W 9 ? *");
+        assert_eq!(format_next(cron, start, 1), ["2023-09-17 00:00:00"]);
+
+        // June 30th 2023 is a Friday
+        let cron = with_weekend("0 0 0 LW 6 ? *");
+        assert_eq!(format_next(cron, start, 1), ["2023-06-29 00:00:00"]);
+
+        // July 28th 2023 is a Friday
+        let cron = with_weekend("0 0 0 L-3W 7 ? *");
+        assert_eq!(format_next(cron, start, 1), ["2023-07-27 00:00:00"]);
+
+        // a Saturday and Sunday weekend keeps Sunday the 17th off
+        let cron = "0 0 0 16W 9 ? *".parse::<Cron>().unwrap();
+        assert_eq!(format_next(cron, start, 1), ["2023-09-15 00:00:00"]);
+    }
+
+    #[test]
+    fn next_after_last_minute_in_hour_moves_to_next_hour() {
+        let cron = "0 */30 * * * ? *".parse::<Cron>().unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 1, 30, 0).unwrap();
+
+        assert_eq!(
+            cron.next_after(start),
+            Some(Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap())
+        );
+    }
+
+    #[test]
+    fn business_days() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        let cron = "0 0 9 BD-2 * ? *".parse::<Cron>().unwrap();
+        assert_eq!(
+            format_next(cron.clone(), start, 3),
+            [
+                "2024-01-30 09:00:00",
+                "2024-02-28 09:00:00",
+                // March 31st 2024 is a Sunday
+                "2024-03-28 09:00:00"
+            ]
+        );
+
+        // Good Friday 2024 is March 29th
+        let holidays = "2024-03-29".parse::<Calendar>().unwrap();
+        let cron = cron.with_holidays(holidays);
+        assert!(cron.contains(Utc.with_ymd_and_hms(2024, 3, 27, 9, 0, 0).unwrap()));
+        assert!(!cron.contains(Utc.with_ymd_and_hms(2024, 3, 28, 9, 0, 0).unwrap()));
+
+        // February 2024 starts on a Thursday, so with a Friday and Saturday weekend the
+        // business days are the 1st, 4th, 5th, ...
+        let cron = "0 0 9 3BD 2 ? *"
+            .parse::<Cron>()
+            .unwrap()
+            .with_weekend(Weekend::FRIDAY_SATURDAY);
+        assert_eq!(format_next(cron, start, 1), ["2024-02-05 09:00:00"]);
+
+        // with only Sundays off, January and May 2024 have 27 business days, and the months
+        // in between fewer
+        let cron = "0 0 9 27BD * ? *"
+            .parse::<Cron>()
+            .unwrap()
+            .with_weekend(Weekend::new([Weekday::Sun]));
+        assert_eq!(
+            format_next(cron, start, 2),
+            ["2024-01-31 09:00:00", "2024-05-31 09:00:00"]
+        );
+    }
+
+    #[test]
+    fn ranges_stay_within_their_bounds() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+
+        // 2024-01-05 is a Friday
+        let cron = "0 30 9 ? * MON-FRI *".parse::<Cron>().unwrap();
+        assert!(cron.contains(Utc.with_ymd_and_hms(2024, 1, 5, 9, 30, 0).unwrap()));
+        assert!(!cron.contains(Utc.with_ymd_and_hms(2024, 1, 6, 9, 30, 0).unwrap()));
+
+        let cron = "0 0 0 1 JAN-MAR ? *".parse::<Cron>().unwrap();
+        assert_eq!(
+            format_next(cron, start, 4),
+            [
+                "2024-02-01 00:00:00",
+                "2024-03-01 00:00:00",
+                "2025-01-01 00:00:00",
+                "2025-02-01 00:00:00"
+            ]
+        );
+
+        // ranges wrapping around start at their first value
+        let cron = "0 0 0 ? * FRI-SUN *".parse::<Cron>().unwrap();
+        assert!(!cron.contains(Utc.with_ymd_and_hms(2024, 1, 4, 0, 0, 0).unwrap()));
+        let cron = "0 0 22-2 * * ? *".parse::<Cron>().unwrap();
+        assert!(!cron.contains(Utc.with_ymd_and_hms(2024, 1, 1, 21, 0, 0).unwrap()));
+        let cron = "0 0 0 1 NOV-FEB ? *".parse::<Cron>().unwrap();
+        assert!(!cron.contains(Utc.with_ymd_and_hms(2024, 10, 1, 0, 0, 0).unwrap()));
+        assert!(cron.contains(Utc.with_ymd_and_hms(2024, 11, 1, 0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn year_ranges_include_both_ends() {
+        let cron = "0 0 0 1 1 ? 2024-2025".parse::<Cron>().unwrap();
+        let start = Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap();
+        assert_eq!(
+            format_next(cron, start, 3),
+            ["2024-01-01 00:00:00", "2025-01-01 00:00:00"]
+        );
+
+        let cron = "0 0 0 1 1 ? 9999-2".parse::<Cron>().unwrap();
+        assert!(cron.contains(Utc.with_ymd_and_hms(2, 1, 1, 0, 0, 0).unwrap()));
+        assert!(!cron.contains(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn later_minutes_of_the_current_hour() {
+        let cron = "0 30 12 * * ? *".parse::<Cron>().unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 2, 1, 12, 0, 0).unwrap();
+        assert_eq!(
+            format_next(cron, start, 2),
+            ["2024-02-01 12:30:00", "2024-02-02 12:30:00"]
+        );
+    }
+
+    #[test]
+    fn multi_crons_match_any_member() {
+        let cron = "0 0 9 ? * MON-FRI * | 0 0 11 ? * SAT,SUN *"
+            .parse::<Cron>()
+            .unwrap();
+        let start = Utc.with_ymd_and_hms(2024, 1, 5, 0, 0, 0).unwrap();
+        assert_eq!(
+            format_next(cron.clone(), start, 4),
+            [
+                "2024-01-05 09:00:00",
+                "2024-01-06 11:00:00",
+                "2024-01-07 11:00:00",
+                "2024-01-08 09:00:00"
+            ]
+        );
+        assert!(cron.contains(Utc.with_ymd_and_hms(2024, 1, 6, 11, 0, 0).unwrap()));
+        assert!(!cron.contains(Utc.with_ymd_and_hms(2024, 1, 6, 9, 0, 0).unwrap()));
+
+        let cron = "0 0 12 1 * ? *;0 30 12 1 * ? *;0 0 12 1 * ? *"
+            .parse::<Cron>()
+            .unwrap();
+        assert_eq!(
+            format_next(cron, start, 3),
+            [
+                "2024-02-01 12:00:00",
+                "2024-02-01 12:30:00",
+                "2024-03-01 12:00:00"
+            ]
+        );
+
+        // settings made before combining are kept, later ones apply to every member
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let friday_saturday = "0 0 9 6W * ? *"
+            .parse::<Cron>()
+            .unwrap()
+            .with_weekend(Weekend::FRIDAY_SATURDAY);
+        let cron = friday_saturday.or("0 0 12 6W * ? *".parse::<Cron>().unwrap());
+        assert_eq!(
+            format_next(cron.clone(), start, 2),
+            ["2024-01-05 12:00:00", "2024-01-07 09:00:00"]
+        );
+        assert_eq!(
+            format_next(cron.with_weekend(Weekend::SATURDAY_SUNDAY), start, 2),
+            ["2024-01-05 09:00:00", "2024-01-05 12:00:00"]
+        );
+
+        assert!("0 0 9 * * ? * |".parse::<Cron>().is_err());
+        assert!("0 0 9 * * ? * | 0 0 25 * * ? *".parse::<Cron>().is_err());
+    }
+
+    #[test]
+    fn iter_yields_every_matching_second() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let cron = "58-1 0 0 * * ? *".parse::<Cron>().unwrap();
+        assert_eq!(
+            format_next(cron, start, 4),
+            [
+                "2024-01-01 00:00:01",
+                "2024-01-01 00:00:58",
+                "2024-01-01 00:00:59",
+                "2024-01-02 00:00:00"
+            ]
+        );
+    }
+
     /// Tests for future time iteration
     mod iter {
         use super::*;
@@ -14,10 +14,10 @@ use core::slice;
 use core::str::FromStr;
 use nom::{
     branch::alt,
-    bytes::complete::tag_no_case,
+    bytes::complete::{tag, tag_no_case},
     character::complete::{char, digit1, space1},
     combinator::{all_consuming, map, map_res, opt},
-    sequence::tuple,
+    sequence::{preceded, terminated, tuple},
     IResult,
 };
 
@@ -253,6 +253,47 @@ impl PartialEq<u32> for DayOfMonthOffset {
     }
 }
 
+/// An "nth" business day of the month, 1-31. Months without that many business days with
+/// the cron's weekend and holidays are skipped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct NthBusinessDay(u32);
+impl Sealed for NthBusinessDay {}
+impl ExprValue for NthBusinessDay {
+    const MAX: u32 = 31;
+    const MIN: u32 = 1;
+
+    fn max() -> Self {
+        Self(Self::MAX)
+    }
+    fn min() -> Self {
+        Self(Self::MIN)
+    }
+}
+impl From<NthBusinessDay> for u32 {
+    #[inline]
+    fn from(m: NthBusinessDay) -> Self {
+        m.0
+    }
+}
+impl TryFrom<u32> for NthBusinessDay {
+    type Error = ValueOutOfRangeError;
+
+    #[inline]
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        if value >= Self::MIN && value <= Self::MAX {
+            Ok(Self(value))
+        } else {
+            Err(ValueOutOfRangeError)
+        }
+    }
+}
+impl PartialEq<u32> for NthBusinessDay {
+    #[inline]
+    fn eq(&self, other: &u32) -> bool {
+        &self.0 == other
+    }
+}
+
 /// A month, 1-12
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Month(u32);
@@ -591,6 +632,20 @@ pub enum Last {
     OffsetWeekday(DayOfMonthOffset),
 }
 
+/// A business day expression for [`DayOfMonthExpr`]. Business days are the days that aren't
+/// part of the weekend or a holiday.
+///
+/// [`DayOfMonthExpr`]: enum.DayOfMonthExpr.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BusinessDay {
+    /// The nth business day of the month. For example, `3BD`, the 3rd business day.
+    Nth(NthBusinessDay),
+    /// The nth business day counted from the end of the month. For example, `BD-2`, the 2nd to
+    /// last business day. `BD-1` is the last business day.
+    NthLast(NthBusinessDay),
+}
+
 /// A day of the month expression.
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
@@ -601,6 +656,8 @@ pub enum DayOfMonthExpr {
     Last(Last),
     /// A 'W' expression, used to mean the closest weekday to the specified day of the month
     ClosestWeekday(DayOfMonth),
+    /// A 'BD' expression, used to mean a business day of the month
+    BusinessDay(BusinessDay),
     /// A '?' character
     Any,
     /// Possibly multiple unique, ranges, or steps
@@ -731,20 +788,183 @@ pub struct CronExpr {
     pub dows: DayOfWeekExpr,
     // The year of the expression
     pub years: Expr<Year>,
+    /// How the day of the month and day of the week parts are combined.
+    pub day_match: DayMatch,
+}
+
+/// How the day of the month and day of the week parts of an expression are combined when both
+/// of them restrict the matching days.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DayMatch {
+    /// A day matches if it matches either part, like in Vixie cron.
+    #[default]
+    Or,
+    /// A day matches only if it matches both parts. For example, `13` and `FRI` together
+    /// only match Friday the 13th.
+    And,
+}
+
+/// Options changing how a cron expression is parsed.
+///
+/// # Example
+/// ```
+/// use cron::parse::{CronExpr, DayMatch, ParseOptions};
+///
+/// // Quartz requires one of the day parts to be a '?'
+/// assert!("0 0 0 13 * FRI *".parse::<CronExpr>().is_err());
+///
+/// let options = ParseOptions::new().both_days(DayMatch::And);
+/// assert!(CronExpr::parse_with(options, "0 0 0 13 * FRI *").is_ok());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ParseOptions {
+    /// Whether expressions restricting both the day of the month and the day of the week are
+    /// accepted, and how the two parts are combined. If `None`, one of the parts must be a '?'.
+    pub both_days: Option<DayMatch>,
 }
 
-/// A formatter for displaying a cron expression description in a specified language
+impl ParseOptions {
+    /// Creates the default options, which follow Quartz.
+    pub fn new() -> Self {
+        Self::default()
+    }
 
-impl CronExpr {}
+    /// Accepts expressions with both day parts set, combining them with the given mode.
+    pub fn both_days(mut self, mode: DayMatch) -> Self {
+        self.both_days = Some(mode);
+        self
+    }
+}
 
 /// An error indicating that the provided cron expression failed to parse
-#[derive(Debug)]
-pub struct CronParseError(());
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronParseError {
+    kind: CronParseErrorKind,
+}
+
+impl CronParseError {
+    /// Returns why the expression failed to parse.
+    pub fn kind(&self) -> CronParseErrorKind {
+        self.kind
+    }
+
+    /// Returns the field that failed to parse, if the error is about a single field.
+    pub fn field(&self) -> Option<Field> {
+        match self.kind {
+            CronParseErrorKind::InvalidField(field) => Some(field),
+            _ => None,
+        }
+    }
+}
 
 impl Display for CronParseError {
     #[inline]
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        "Failed to parse cron expression".fmt(f)
+        write!(f, "Failed to parse cron expression: {}", self.kind)
+    }
+}
+
+/// The reason a cron expression failed to parse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CronParseErrorKind {
+    /// The expression doesn't have 7 fields. Contains the number of fields found.
+    FieldCount(usize),
+    /// A field has an invalid value.
+    InvalidField(Field),
+    /// Both the day of the month and day of the week fields restrict days, but the parse
+    /// options require one of them to be a '?'.
+    BothDays,
+    /// The expression has unexpected characters around its fields.
+    Malformed,
+}
+
+impl Display for CronParseErrorKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            CronParseErrorKind::FieldCount(count) => {
+                write!(f, "expected 7 fields but found {}", count)
+            }
+            CronParseErrorKind::InvalidField(field) => write!(f, "invalid {} field", field),
+            CronParseErrorKind::BothDays => {
+                "only one of the day of month and day of week fields may be set".fmt(f)
+            }
+            CronParseErrorKind::Malformed => "unexpected characters around the fields".fmt(f),
+        }
+    }
+}
+
+/// A field of a cron expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Field {
+    /// The 1st field, seconds
+    Seconds,
+    /// The 2nd field, minutes
+    Minutes,
+    /// The 3rd field, hours
+    Hours,
+    /// The 4th field, days of the month
+    DaysOfMonth,
+    /// The 5th field, months
+    Months,
+    /// The 6th field, days of the week
+    DaysOfWeek,
+    /// The 7th field, years
+    Years,
+}
+
+impl Field {
+    /// All fields, in the order they appear in an expression.
+    pub const ALL: [Field; 7] = [
+        Field::Seconds,
+        Field::Minutes,
+        Field::Hours,
+        Field::DaysOfMonth,
+        Field::Months,
+        Field::DaysOfWeek,
+        Field::Years,
+    ];
+
+    /// Returns the position (starting at 0) of the field in an expression.
+    pub fn index(self) -> usize {
+        self as usize
+    }
+
+    /// Returns the name of the field in lowercase English, like "day of month".
+    pub fn name(self) -> &'static str {
+        match self {
+            Field::Seconds => "second",
+            Field::Minutes => "minute",
+            Field::Hours => "hour",
+            Field::DaysOfMonth => "day of month",
+            Field::Months => "month",
+            Field::DaysOfWeek => "day of week",
+            Field::Years => "year",
+        }
+    }
+
+    fn parses(self, s: &str) -> bool {
+        fn full<O>(s: &str, f: impl Fn(&str) -> IResult<&str, O>) -> bool {
+            all_consuming(f)(s).is_ok()
+        }
+
+        match self {
+            Field::Seconds => full(s, seconds_expr),
+            Field::Minutes => full(s, minutes_expr),
+            Field::Hours => full(s, hours_expr),
+            Field::DaysOfMonth => full(s, dom_expr),
+            Field::Months => full(s, months_expr),
+            Field::DaysOfWeek => full(s, dow_expr),
+            Field::Years => full(s, years_expr),
+        }
+    }
+}
+
+impl Display for Field {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        self.name().fmt(f)
     }
 }
 
@@ -909,6 +1129,14 @@ fn hours_expr(s: &str) -> IResult<&str, Expr<Hour>> {
 fn dom_expr(input: &str) -> IResult<&str, DayOfMonthExpr> {
     let dom = map_digit1::<DayOfMonth>();
 
+    let (input, business_day) = opt(alt((
+        map(preceded(tag("BD-"), map_digit1()), BusinessDay::NthLast),
+        map(terminated(map_digit1(), tag("BD")), BusinessDay::Nth),
+    )))(input)?;
+    if let Some(business_day) = business_day {
+        return Ok((input, DayOfMonthExpr::BusinessDay(business_day)));
+    }
+
     let (input, start) = opt(alt((char('?'), char('*'), char('L'))))(input)?;
 
     match start {
@@ -1096,6 +1324,13 @@ impl FromStr for CronExpr {
 
     #[inline]
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_with(ParseOptions::default(), s)
+    }
+}
+
+impl CronExpr {
+    /// Parses a cron expression using the given options.
+    pub fn parse_with(options: ParseOptions, s: &str) -> Result<Self, CronParseError> {
         let ret = all_consuming(map(
             tuple((
                 seconds_expr,
@@ -1120,18 +1355,34 @@ impl FromStr for CronExpr {
                 months,
                 dows,
                 years,
+                day_match: DayMatch::default(),
             },
         ))(s);
 
         match ret {
-            Ok((_, expr)) => Self::check(expr),
-            Err(_) => return Err(CronParseError(())),
+            Ok((_, expr)) => Self::check(expr, options),
+            Err(_) => Err(Self::locate_error(s)),
         }
     }
-}
 
-impl CronExpr {
-    fn check(expr: CronExpr) -> Result<Self, CronParseError> {
+    /// Finds out why an expression failed to parse by checking each field on its own.
+    fn locate_error(s: &str) -> CronParseError {
+        let fields: Vec<&str> = s.split_whitespace().collect();
+        let kind = if fields.len() != Field::ALL.len() {
+            CronParseErrorKind::FieldCount(fields.len())
+        } else {
+            Field::ALL
+                .iter()
+                .zip(fields)
+                .find(|(field, value)| !field.parses(value))
+                .map_or(CronParseErrorKind::Malformed, |(&field, _)| {
+                    CronParseErrorKind::InvalidField(field)
+                })
+        };
+        CronParseError { kind }
+    }
+
+    fn check(mut expr: CronExpr, options: ParseOptions) -> Result<Self, CronParseError> {
         let cloned_expr = expr.clone();
 
         // check day of month and day of weekday
@@ -1139,7 +1390,14 @@ impl CronExpr {
             (DayOfMonthExpr::Any, DayOfWeekExpr::Any) => {}
             (DayOfMonthExpr::Any, _) => {}
             (_, DayOfWeekExpr::Any) => {}
-            (_, _) => return Err(CronParseError(())),
+            (_, _) => match options.both_days {
+                Some(mode) => expr.day_match = mode,
+                None => {
+                    return Err(CronParseError {
+                        kind: CronParseErrorKind::BothDays,
+                    })
+                }
+            },
         }
 
         Ok(expr)
@@ -1371,6 +1629,41 @@ mod tests {
 
             test_crons(crons)
         }
+
+        #[test]
+        fn parse_error_kinds() {
+            let kind = |s: &str| s.parse::<CronExpr>().unwrap_err().kind();
+
+            assert_eq!(kind("0 0 12 * * ?"), CronParseErrorKind::FieldCount(6));
+            assert_eq!(
+                kind("0 0 12 32 * ? *"),
+                CronParseErrorKind::InvalidField(Field::DaysOfMonth)
+            );
+            assert_eq!(
+                kind("0 60 12 * * ? *"),
+                CronParseErrorKind::InvalidField(Field::Minutes)
+            );
+            assert_eq!(kind("0 0 12 1 * MON *"), CronParseErrorKind::BothDays);
+            assert_eq!(kind(" 0 0 12 * * ? *"), CronParseErrorKind::Malformed);
+
+            let error = "0 0 12 * FOO ? *".parse::<CronExpr>().unwrap_err();
+            assert_eq!(error.field(), Some(Field::Months));
+            assert_eq!(error.field().map(Field::index), Some(4));
+        }
+
+        #[test]
+        fn parse_both_days() {
+            let strict = "0 0 0 13 * FRI *".parse::<CronExpr>();
+            assert!(strict.is_err());
+
+            let options = ParseOptions::new().both_days(DayMatch::And);
+            let expr = CronExpr::parse_with(options, "0 0 0 13 * FRI *").unwrap();
+            assert_eq!(expr.day_match, DayMatch::And);
+
+            // a '?' keeps the default mode, since only one part restricts the days
+            let expr = CronExpr::parse_with(options, "0 0 0 13 * ? *").unwrap();
+            assert_eq!(expr.day_match, DayMatch::Or);
+        }
     }
 
     mod minutes {
@@ -1929,6 +2222,33 @@ mod tests {
             )
         }
 
+        #[test]
+        fn business_day() {
+            assert_eq!(
+                dom_expr("3BD"),
+                Ok(("", DayOfMonthExpr::BusinessDay(BusinessDay::Nth(e(3)))))
+            );
+            assert_eq!(
+                dom_expr("BD-2"),
+                Ok(("", DayOfMonthExpr::BusinessDay(BusinessDay::NthLast(e(2)))))
+            );
+        }
+
+        #[test]
+        fn business_day_limits() {
+            assert!(matches!(dom_expr("BD-0"), Err(_)));
+            assert!(matches!(dom_expr("BD-32"), Err(_)));
+            assert!("0 0 0 0BD * ? *".parse::<CronExpr>().is_err());
+            assert!("0 0 0 32BD * ? *".parse::<CronExpr>().is_err());
+
+            // a one day weekend leaves more than 23 business days
+            assert_eq!(
+                dom_expr("26BD"),
+                Ok(("", DayOfMonthExpr::BusinessDay(BusinessDay::Nth(e(26)))))
+            );
+            assert!("0 0 0 BD-31 * ? *".parse::<CronExpr>().is_ok());
+        }
+
         #[test]
         fn star_step() {
             assert_eq!(
@@ -1,41 +1,229 @@
+use crate::calendar::{self, Calendar};
+use crate::clock::{Clock, SystemClock};
+use crate::compose::{self, Times, MAX_STEPS};
 use crate::parse::CronParseError;
 use crate::Cron;
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{
+    DateTime, Datelike, Duration as ChronoDuration, LocalResult, NaiveDate, NaiveDateTime,
+    NaiveTime, Offset, TimeZone, Utc, Weekday,
+};
+use core::fmt::{self, Display, Formatter};
 use priority_queue::PriorityQueue;
+use serde_json::{json, Value};
 use std::cmp::Reverse;
-use std::collections::HashMap;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeSet, BinaryHeap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
 use tokio::time::Duration;
 
 pub trait Trigger: Send + Sync {
-    fn get_next(&self, from: i64) -> i64;
+    /// Returns the first fire time after `from`, or `None` if the trigger won't fire again.
+    /// Times are in milliseconds since the Unix epoch.
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError>;
     fn get_id(&self) -> String;
+
+    /// Returns a value identifying the trigger's schedule. When jobs are reloaded with
+    /// [`Scheduler::reload`], a job whose definition changed is rescheduled, and a job whose
+    /// definition didn't keeps its registered trigger.
+    ///
+    /// [`Scheduler::reload`]: struct.Scheduler.html#method.reload
+    fn definition(&self) -> String;
+
+    /// Called by the scheduler when the trigger fires at the given time, before its next fire
+    /// time is computed. The default does nothing.
+    fn triggered(&self, firetime: i64) {
+        let _ = firetime;
+    }
+
+    /// Returns how many milliseconds after a fire time the job is due, to spread jobs with the
+    /// same fire times. Jobs are run when due but still given their fire time. The default is
+    /// 0.
+    fn jitter(&self, firetime: i64) -> i64 {
+        let _ = firetime;
+        0
+    }
+}
+
+/// An error indicating that a trigger failed to compute its next fire time
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TriggerError {
+    message: String,
+}
+
+impl TriggerError {
+    /// Creates an error with the given message.
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+        }
+    }
+
+    /// Creates an error for a time in milliseconds since the Unix epoch that's out of the
+    /// range of times triggers can handle.
+    pub fn out_of_range(time: i64) -> Self {
+        Self::new(format!("time {} is out of range", time))
+    }
+
+    /// Returns the error's message.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
 }
 
+impl Display for TriggerError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(f, "Trigger failed: {}", self.message)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for TriggerError {}
+
+/// An error changing the jobs of a [`Scheduler`].
+///
+/// [`Scheduler`]: struct.Scheduler.html
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchedulerError {
+    /// A job with the id is already registered.
+    DuplicateId(String),
+    /// No job with the id is registered.
+    UnknownId(String),
+    /// The job was changed since the version an update was made against.
+    StaleVersion {
+        id: String,
+        expected: u64,
+        current: u64,
+    },
+}
+
+impl Display for SchedulerError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            SchedulerError::DuplicateId(id) => write!(f, "Job {} is already registered", id),
+            SchedulerError::UnknownId(id) => write!(f, "Job {} isn't registered", id),
+            SchedulerError::StaleVersion {
+                id,
+                expected,
+                current,
+            } => write!(
+                f,
+                "Job {} is at version {}, not version {}",
+                id, current, expected
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for SchedulerError {}
+
+/// A trigger firing at a fixed interval.
+///
+/// Without an anchor, each fire time is one interval after the previous one, or after the time
+/// the job was added. With an anchor, the trigger fires at the anchor plus a whole number of
+/// intervals, so its times don't depend on when the job was added. Anchoring at the Unix
+/// epoch aligns intervals dividing a day to wall clock boundaries, like every 15 minutes at
+/// :00, :15, :30 and :45.
+///
+/// # Example
+/// ```
+/// use cron::scheduler::{EveryTrigger, Trigger};
+/// use chrono::prelude::*;
+/// use std::time::Duration;
+///
+/// let trigger = EveryTrigger::new(Duration::from_secs(90 * 60), "id".to_string())
+///     .with_anchor(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+///
+/// let from = Utc.with_ymd_and_hms(2024, 1, 1, 2, 0, 0).unwrap();
+/// let next = Utc.with_ymd_and_hms(2024, 1, 1, 3, 0, 0).unwrap();
+/// assert_eq!(trigger.get_next(from.timestamp_millis()), Ok(Some(next.timestamp_millis())));
+/// ```
 pub struct EveryTrigger {
     interval: Duration,
     id: String,
+    anchor: Option<i64>,
+    start: Option<i64>,
+    end: Option<i64>,
 }
 
 impl EveryTrigger {
     pub fn new(interval: Duration, id: String) -> Self {
-        Self { interval, id }
+        Self {
+            interval,
+            id,
+            anchor: None,
+            start: None,
+            end: None,
+        }
+    }
+
+    /// Fires at the anchor plus a whole number of intervals. The anchor may be before or after
+    /// the time the job is added.
+    pub fn with_anchor(mut self, anchor: DateTime<Utc>) -> Self {
+        self.anchor = Some(anchor.timestamp_millis());
+        self
+    }
+
+    /// Doesn't fire before the given time. Without an anchor, the start is used as the anchor,
+    /// so the first fire time is the start.
+    pub fn with_start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = Some(start.timestamp_millis());
+        self
+    }
+
+    /// Doesn't fire after the given time.
+    pub fn with_end(mut self, end: DateTime<Utc>) -> Self {
+        self.end = Some(end.timestamp_millis());
+        self
     }
 }
 
 impl Trigger for EveryTrigger {
-    fn get_next(&self, from: i64) -> i64 {
-        self.interval.as_millis() as i64 + from
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        let interval = i64::try_from(self.interval.as_millis())
+            .ok()
+            .filter(|&interval| interval > 0)
+            .ok_or_else(|| TriggerError::new("the interval must be at least a millisecond"))?;
+
+        let next = match self.anchor.or(self.start) {
+            Some(anchor) => {
+                let from = match self.start {
+                    Some(start) => from.max(start.saturating_sub(1)),
+                    None => from,
+                };
+                from.checked_sub(anchor)
+                    .map(|elapsed| elapsed.div_euclid(interval) + 1)
+                    .and_then(|periods| periods.checked_mul(interval))
+                    .and_then(|offset| anchor.checked_add(offset))
+            }
+            None => from.checked_add(interval),
+        }
+        .ok_or_else(|| TriggerError::out_of_range(from))?;
+
+        Ok(Some(next).filter(|&next| !matches!(self.end, Some(end) if next > end)))
     }
 
     fn get_id(&self) -> String {
         return self.id.clone();
     }
+
+    fn definition(&self) -> String {
+        format!(
+            "every {:?} {:?} {:?} {:?}",
+            self.interval, self.anchor, self.start, self.end
+        )
+    }
 }
 
 pub struct CronTrigger {
     id: String,
+    /// The expression the cron was parsed from
+    source: String,
     cron: Cron,
+    calendar: Option<Calendar>,
 }
 
 impl CronTrigger {
@@ -43,91 +231,2463 @@ impl CronTrigger {
         let opt = cron.parse::<Cron>();
 
         match opt {
-            Ok(expr) => Ok(CronTrigger { id: id, cron: expr }),
+            Ok(expr) => Ok(CronTrigger {
+                id: id,
+                source: cron.to_string(),
+                cron: expr,
+                calendar: None,
+            }),
             Err(e) => Err(e),
         }
     }
+
+    /// Creates a trigger firing at the times of an already parsed cron, such as one from a
+    /// crontab entry, with the expression it was parsed from.
+    pub fn from_cron(id: String, source: &str, cron: Cron) -> Self {
+        CronTrigger {
+            id,
+            source: source.to_string(),
+            cron,
+            calendar: None,
+        }
+    }
+
+    /// Skips all fire times excluded by the given calendar.
+    pub fn with_calendar(mut self, calendar: Calendar) -> Self {
+        self.calendar = Some(calendar);
+        self
+    }
 }
 
 impl Trigger for CronTrigger {
-    fn get_next(&self, from: i64) -> i64 {
-        let naive = NaiveDateTime::from_timestamp_millis(from as i64).unwrap();
-        let datetime: DateTime<Utc> = DateTime::from_utc(naive, Utc);
-        let next = self.cron.next_after(datetime).unwrap();
-        next.timestamp_millis() as i64
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        let datetime = DateTime::from_timestamp_millis(from)
+            .ok_or_else(|| TriggerError::out_of_range(from))?;
+        let next = match &self.calendar {
+            Some(calendar) => calendar::next_after(&self.cron, calendar, datetime),
+            None => self.cron.next_after(datetime),
+        };
+        Ok(next.map(|next| next.timestamp_millis()))
     }
 
     fn get_id(&self) -> String {
         return self.id.clone();
     }
+
+    fn definition(&self) -> String {
+        match &self.calendar {
+            Some(calendar) => {
+                // calendars have no source, so they're told apart by their hash
+                let mut hasher = DefaultHasher::new();
+                calendar.hash(&mut hasher);
+                format!("cron {} excluding {:016x}", self.source, hasher.finish())
+            }
+            None => format!("cron {}", self.source),
+        }
+    }
 }
 
-#[derive(Clone)]
-pub struct Scheduler {
-    triggers: Arc<Mutex<HashMap<String, Arc<dyn Trigger>>>>,
-    queue: Arc<Mutex<PriorityQueue<String, Reverse<i64>>>>,
+/// A trigger firing once at a given time.
+pub struct AtTrigger {
+    id: String,
+    at: i64,
 }
 
-impl Scheduler {
-    pub fn new() -> Self {
-        let queue = PriorityQueue::<String, Reverse<i64>>::new();
-        let triggers = HashMap::<String, Arc<dyn Trigger>>::new();
+impl AtTrigger {
+    /// Creates a trigger firing once at the given time, or never if it's already passed.
+    pub fn new(id: String, at: DateTime<Utc>) -> Self {
         Self {
-            triggers: Arc::new(Mutex::new(triggers)),
-            queue: Arc::new(Mutex::new(queue)),
+            id,
+            at: at.timestamp_millis(),
         }
     }
+}
 
-    pub fn add_job(&mut self, job: Arc<dyn Trigger>) {
-        let last_firetime = job.get_next(Utc::now().timestamp_millis() as i64);
-        let id = job.get_id();
+impl Trigger for AtTrigger {
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        Ok(Some(self.at).filter(|&at| at > from))
+    }
 
-        let mut trigger = self.triggers.lock().unwrap();
-        let mut queue = self.queue.lock().unwrap();
-        trigger.insert(id.clone(), job);
-        queue.push(id, Reverse(last_firetime));
+    fn get_id(&self) -> String {
+        self.id.clone()
     }
 
-    pub fn add_job_with_previous_time(&mut self, job: Arc<dyn Trigger>, last_firetime: i64) {
-        let id = job.get_id();
+    fn definition(&self) -> String {
+        format!("at {}", self.at)
+    }
+}
 
-        let mut trigger = self.triggers.lock().unwrap();
-        let mut queue = self.queue.lock().unwrap();
-        trigger.insert(id.clone(), job);
-        queue.push(id, Reverse(last_firetime));
+/// A trigger firing at each of a list of times.
+pub struct DatesTrigger {
+    id: String,
+    /// The fire times, sorted and without duplicates
+    dates: Vec<i64>,
+}
+
+impl DatesTrigger {
+    /// Creates a trigger firing at the given times, in any order.
+    pub fn new<I>(id: String, dates: I) -> Self
+    where
+        I: IntoIterator<Item = DateTime<Utc>>,
+    {
+        let mut dates: Vec<i64> = dates.into_iter().map(|d| d.timestamp_millis()).collect();
+        dates.sort_unstable();
+        dates.dedup();
+        Self { id, dates }
     }
+}
 
-    pub fn remove_job(&mut self, id: String) {
-        let mut trigger = self.triggers.lock().unwrap();
-        let mut queue = self.queue.lock().unwrap();
+impl Trigger for DatesTrigger {
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        let index = self.dates.partition_point(|&date| date <= from);
+        Ok(self.dates.get(index).copied())
+    }
 
-        queue.remove(&id);
-        trigger.remove(&id);
+    fn get_id(&self) -> String {
+        self.id.clone()
     }
 
-    pub fn get_next_firetime(&mut self) -> Option<i64> {
-        let trigger = self.triggers.lock().unwrap();
-        let mut queue = self.queue.lock().unwrap();
+    fn definition(&self) -> String {
+        format!("dates {:?}", self.dates)
+    }
+}
 
-        let t = queue.pop();
+/// A calendar unit a [`CalendarIntervalTrigger`] steps in.
+///
+/// [`CalendarIntervalTrigger`]: struct.CalendarIntervalTrigger.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CalendarUnit {
+    Days,
+    Weeks,
+    Months,
+    Years,
+}
 
-        match t {
-            Some(job) => {
-                let job_id = job.0;
-                let last_fire_time = job.1;
+/// What a [`CalendarIntervalTrigger`] stepping in months or years does when the anchor's day
+/// doesn't exist in a month, like the 31st in April or the 29th of February in a common year.
+///
+/// [`CalendarIntervalTrigger`]: struct.CalendarIntervalTrigger.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DayOverflow {
+    /// Fires on the last day of the month.
+    #[default]
+    Clamp,
+    /// Doesn't fire that month.
+    Skip,
+    /// Fires in the next month, as many days after its start as the day overflows the month.
+    RollOver,
+}
+
+/// A trigger firing every number of days, weeks, months or years from an anchor, at the
+/// anchor's local time of day in its time zone.
+///
+/// Steps are taken on the local date, so with a time zone observing daylight saving time,
+/// like `chrono::Local`, the trigger keeps firing at the same local time across changes. A
+/// local time repeated when clocks go back fires at its first occurrence, and one skipped when
+/// clocks go forward fires that much later.
+///
+/// # Example
+/// ```
+/// use cron::scheduler::{CalendarIntervalTrigger, CalendarUnit, Trigger};
+/// use chrono::prelude::*;
+///
+/// // every 3 months on the 31st, clamped to the end of shorter months
+/// let anchor = Utc.with_ymd_and_hms(2024, 1, 31, 9, 0, 0).unwrap();
+/// let trigger = CalendarIntervalTrigger::new("id".to_string(), anchor, 3, CalendarUnit::Months);
+///
+/// let next = Utc.with_ymd_and_hms(2024, 4, 30, 9, 0, 0).unwrap();
+/// assert_eq!(trigger.get_next(anchor.timestamp_millis()), Ok(Some(next.timestamp_millis())));
+/// ```
+pub struct CalendarIntervalTrigger<Tz: TimeZone = Utc> {
+    id: String,
+    anchor: DateTime<Tz>,
+    every: u32,
+    unit: CalendarUnit,
+    overflow: DayOverflow,
+}
+
+impl<Tz: TimeZone> CalendarIntervalTrigger<Tz> {
+    /// Creates a trigger firing at the anchor and every `every` units after it.
+    pub fn new(id: String, anchor: DateTime<Tz>, every: u32, unit: CalendarUnit) -> Self {
+        Self {
+            id,
+            anchor,
+            every,
+            unit,
+            overflow: DayOverflow::default(),
+        }
+    }
+
+    /// Sets what happens when the anchor's day doesn't exist in a month.
+    pub fn with_overflow(mut self, overflow: DayOverflow) -> Self {
+        self.overflow = overflow;
+        self
+    }
+
+    /// Returns the number of steps from the anchor to the given local time, rounded down.
+    fn steps_to(&self, local: NaiveDateTime) -> i64 {
+        let anchor = self.anchor.naive_local();
+        let elapsed = match self.unit {
+            CalendarUnit::Days => (local.date() - anchor.date()).num_days(),
+            CalendarUnit::Weeks => (local.date() - anchor.date()).num_weeks(),
+            CalendarUnit::Months => {
+                i64::from(local.year() - anchor.year()) * 12 + i64::from(local.month())
+                    - i64::from(anchor.month())
+            }
+            CalendarUnit::Years => i64::from(local.year() - anchor.year()),
+        };
+        elapsed.div_euclid(i64::from(self.every))
+    }
+
+    /// Returns the time of the given step from the anchor, `Some(None)` if the step is
+    /// skipped, or `None` if it's out of range.
+    fn occurrence(&self, step: i64) -> Option<Option<i64>> {
+        let anchor = self.anchor.naive_local();
+        let units = step.checked_mul(i64::from(self.every))?;
+        let date = match self.unit {
+            CalendarUnit::Days => anchor
+                .date()
+                .checked_add_signed(ChronoDuration::try_days(units)?),
+            CalendarUnit::Weeks => anchor
+                .date()
+                .checked_add_signed(ChronoDuration::try_weeks(units)?),
+            CalendarUnit::Months | CalendarUnit::Years => {
+                let months = match self.unit {
+                    CalendarUnit::Years => units.checked_mul(12)?,
+                    _ => units,
+                };
+                let index = i64::from(anchor.year()) * 12 + i64::from(anchor.month0()) + months;
+                let year = i32::try_from(index.div_euclid(12)).ok()?;
+                let month = index.rem_euclid(12) as u32 + 1;
+                let first = NaiveDate::from_ymd_opt(year, month, 1)?;
+                let last = days_in_month(first)?;
+                let day = anchor.day();
+                if day <= last {
+                    first.with_day(day)
+                } else {
+                    match self.overflow {
+                        DayOverflow::Clamp => first.with_day(last),
+                        DayOverflow::Skip => return Some(None),
+                        DayOverflow::RollOver => {
+                            first.checked_add_signed(ChronoDuration::days(i64::from(day - 1)))
+                        }
+                    }
+                }
+            }
+        }?;
+        let local = date.and_time(anchor.time());
+        resolve_local(&self.anchor.timezone(), local).map(Some)
+    }
+}
+
+/// Returns the time of a local time in a time zone, in milliseconds since the Unix epoch. A
+/// local time repeated when clocks go back resolves to its first occurrence, and one skipped
+/// when clocks go forward to as much later as the clocks moved.
+fn resolve_local<Tz: TimeZone>(tz: &Tz, local: NaiveDateTime) -> Option<i64> {
+    let time = match tz.from_local_datetime(&local) {
+        LocalResult::Single(time) | LocalResult::Ambiguous(time, _) => time,
+        LocalResult::None => {
+            // use the offset from before the change
+            let before = local.checked_sub_signed(ChronoDuration::days(1))?;
+            let offset = tz.offset_from_local_datetime(&before).earliest()?.fix();
+            tz.from_utc_datetime(&(local - offset))
+        }
+    };
+    Some(time.timestamp_millis())
+}
+
+/// Returns the number of days in the month starting at the given date.
+fn days_in_month(first: NaiveDate) -> Option<u32> {
+    let next = match first.month() {
+        12 => NaiveDate::from_ymd_opt(first.year() + 1, 1, 1)?,
+        month => NaiveDate::from_ymd_opt(first.year(), month + 1, 1)?,
+    };
+    Some((next - first).num_days() as u32)
+}
+
+impl<Tz> Trigger for CalendarIntervalTrigger<Tz>
+where
+    Tz: TimeZone + Send + Sync,
+    Tz::Offset: Send + Sync,
+{
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        if self.every == 0 {
+            return Err(TriggerError::new("the interval must be at least 1"));
+        }
+        let local = self
+            .anchor
+            .timezone()
+            .timestamp_millis_opt(from)
+            .single()
+            .ok_or_else(|| TriggerError::out_of_range(from))?
+            .naive_local();
+
+        // start a step early, as the local time of day and overflowing days may put the
+        // occurrence of the estimated step after `from`. skipped days never repeat more than a
+        // few times in a row, so give up after enough of them.
+        let first = (self.steps_to(local) - 1).max(0);
+        for step in first..first + 64 {
+            match self.occurrence(step) {
+                Some(Some(time)) if time > from => return Ok(Some(time)),
+                Some(_) => {}
+                None => return Ok(None),
+            }
+        }
+        Ok(None)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn definition(&self) -> String {
+        format!(
+            "every {} {:?} from {} {:?}",
+            self.every,
+            self.unit,
+            self.anchor.to_rfc3339(),
+            self.overflow
+        )
+    }
+}
+
+/// A trigger firing at an interval within a daily time window, on some days of the week.
+/// The interval restarts at the start of each window, and the end of the window is the last
+/// time it may fire. Times of day are local to the trigger's time zone, UTC by default.
+///
+/// # Example
+/// ```
+/// use cron::scheduler::{DailyTimeIntervalTrigger, Trigger};
+/// use chrono::prelude::*;
+/// use std::time::Duration;
+///
+/// // every 7 minutes from 08:30 to 17:45, Monday to Friday
+/// let trigger = DailyTimeIntervalTrigger::new(
+///     "id".to_string(),
+///     Duration::from_secs(7 * 60),
+///     NaiveTime::from_hms_opt(8, 30, 0).unwrap(),
+///     NaiveTime::from_hms_opt(17, 45, 0).unwrap(),
+/// )
+/// .with_days([Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri]);
+///
+/// // the last time on Friday the 5th is 17:43, and the next window starts on Monday
+/// let from = Utc.with_ymd_and_hms(2024, 1, 5, 17, 43, 0).unwrap();
+/// let next = Utc.with_ymd_and_hms(2024, 1, 8, 8, 30, 0).unwrap();
+/// assert_eq!(trigger.get_next(from.timestamp_millis()), Ok(Some(next.timestamp_millis())));
+/// ```
+pub struct DailyTimeIntervalTrigger<Tz: TimeZone = Utc> {
+    id: String,
+    interval: Duration,
+    start: NaiveTime,
+    end: NaiveTime,
+    /// The days of the week to fire on, a bit per day from Sunday
+    days: u8,
+    timezone: Tz,
+}
 
-                let trigger_opt = trigger.get(&job_id);
+impl DailyTimeIntervalTrigger {
+    /// Creates a trigger firing every day from the start time to the end time, in UTC.
+    pub fn new(id: String, interval: Duration, start: NaiveTime, end: NaiveTime) -> Self {
+        Self {
+            id,
+            interval,
+            start,
+            end,
+            days: 0b0111_1111,
+            timezone: Utc,
+        }
+    }
+}
+
+impl<Tz: TimeZone> DailyTimeIntervalTrigger<Tz> {
+    /// Fires only on the given days of the week.
+    pub fn with_days<I>(mut self, days: I) -> Self
+    where
+        I: IntoIterator<Item = Weekday>,
+    {
+        self.days = days
+            .into_iter()
+            .fold(0, |bits, day| bits | (1 << day.num_days_from_sunday()));
+        self
+    }
+
+    /// Uses times of day and days of the week local to the given time zone.
+    pub fn with_timezone<Tz2: TimeZone>(self, timezone: Tz2) -> DailyTimeIntervalTrigger<Tz2> {
+        DailyTimeIntervalTrigger {
+            id: self.id,
+            interval: self.interval,
+            start: self.start,
+            end: self.end,
+            days: self.days,
+            timezone,
+        }
+    }
+
+    fn fires_on(&self, day: Weekday) -> bool {
+        self.days & (1 << day.num_days_from_sunday()) != 0
+    }
+}
 
-                match trigger_opt {
-                    Some(tigger) => {
-                        let next_firetime = tigger.get_next(last_fire_time.0);
-                        queue.push(job_id, Reverse(next_firetime));
-                        Some(next_firetime)
+impl<Tz> Trigger for DailyTimeIntervalTrigger<Tz>
+where
+    Tz: TimeZone + Send + Sync,
+    Tz::Offset: Send + Sync,
+{
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        let interval = i64::try_from(self.interval.as_millis())
+            .ok()
+            .filter(|&interval| interval > 0)
+            .ok_or_else(|| TriggerError::new("the interval must be at least a millisecond"))?;
+        if self.end < self.start {
+            return Err(TriggerError::new("the time window ends before it starts"));
+        }
+        let local = self
+            .timezone
+            .timestamp_millis_opt(from)
+            .single()
+            .ok_or_else(|| TriggerError::out_of_range(from))?
+            .naive_local();
+
+        let window = (self.end - self.start).num_milliseconds();
+        let mut date = local.date();
+        // a day of each week and the day after for a window ending after `from`
+        for _ in 0..8 {
+            if self.fires_on(date.weekday()) {
+                let start = date.and_time(self.start);
+                // skip the window's times already passed, staying a step early in case the
+                // time zone's offset changes during the window
+                let passed = (local - start).num_milliseconds().max(0) / interval;
+                let mut offset = (passed - 1).max(0) * interval;
+                while offset <= window {
+                    let time = start + ChronoDuration::milliseconds(offset);
+                    match resolve_local(&self.timezone, time) {
+                        Some(time) if time > from => return Ok(Some(time)),
+                        Some(_) => {}
+                        None => return Err(TriggerError::out_of_range(from)),
                     }
-                    None => None,
+                    offset += interval;
                 }
             }
-            None => None,
+            date = match date.succ_opt() {
+                Some(date) => date,
+                None => return Ok(None),
+            };
+        }
+        Ok(None)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn definition(&self) -> String {
+        format!(
+            "every {:?} from {} to {} on {:07b} in {:?}",
+            self.interval,
+            self.start,
+            self.end,
+            self.days,
+            self.timezone
+                .offset_from_utc_datetime(&DateTime::UNIX_EPOCH.naive_utc())
+        )
+    }
+}
+
+/// A trigger limiting another to fire only between a start and an end time, and at most a
+/// number of times.
+///
+/// Before the start, the inner trigger is asked for its first time from the start, so a
+/// trigger counting from the time it's asked from, like an [`EveryTrigger`] without an
+/// anchor, should be anchored to keep its phase.
+///
+/// # Example
+/// ```
+/// use cron::scheduler::{BoundedTrigger, CronTrigger, Trigger};
+/// use chrono::prelude::*;
+///
+/// let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+/// let daily = CronTrigger::new("daily".to_string(), "0 0 0 * * ? *").unwrap();
+/// let trigger = BoundedTrigger::new(daily)
+///     .with_start(start)
+///     .with_max_fires(2);
+///
+/// let first = trigger.get_next(0).unwrap().unwrap();
+/// assert_eq!(first, start.timestamp_millis());
+/// trigger.triggered(first);
+/// assert_eq!(trigger.remaining(), Some(1));
+/// ```
+///
+/// [`EveryTrigger`]: struct.EveryTrigger.html
+pub struct BoundedTrigger<T> {
+    trigger: T,
+    start: Option<i64>,
+    end: Option<i64>,
+    max_fires: Option<u32>,
+    fired: AtomicU32,
+}
+
+impl<T: Trigger> BoundedTrigger<T> {
+    /// Wraps a trigger without any bounds.
+    pub fn new(trigger: T) -> Self {
+        Self {
+            trigger,
+            start: None,
+            end: None,
+            max_fires: None,
+            fired: AtomicU32::new(0),
+        }
+    }
+
+    /// Doesn't fire before the given time, skipping the wrapped trigger's earlier times.
+    pub fn with_start(mut self, start: DateTime<Utc>) -> Self {
+        self.start = Some(start.timestamp_millis());
+        self
+    }
+
+    /// Doesn't fire after the given time.
+    pub fn with_end(mut self, end: DateTime<Utc>) -> Self {
+        self.end = Some(end.timestamp_millis());
+        self
+    }
+
+    /// Fires at most the given number of times.
+    pub fn with_max_fires(mut self, max_fires: u32) -> Self {
+        self.max_fires = Some(max_fires);
+        self
+    }
+
+    /// Returns the wrapped trigger.
+    pub fn inner(&self) -> &T {
+        &self.trigger
+    }
+
+    /// Returns the number of times the trigger fired.
+    pub fn fired(&self) -> u32 {
+        self.fired.load(Ordering::SeqCst)
+    }
+
+    /// Returns the number of times the trigger may still fire, or `None` if that isn't limited.
+    pub fn remaining(&self) -> Option<u32> {
+        self.max_fires
+            .map(|max_fires| max_fires.saturating_sub(self.fired()))
+    }
+}
+
+impl<T: Trigger> Trigger for BoundedTrigger<T> {
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        if self.remaining() == Some(0) {
+            return Ok(None);
+        }
+        // jump straight to the start rather than stepping through the times before it
+        let from = match self.start {
+            Some(start) => from.max(start.saturating_sub(1)),
+            None => from,
+        };
+        let next = self.trigger.get_next(from)?;
+        Ok(next.filter(|&next| !matches!(self.end, Some(end) if next > end)))
+    }
+
+    fn get_id(&self) -> String {
+        self.trigger.get_id()
+    }
+
+    fn definition(&self) -> String {
+        format!(
+            "{} bounded {:?} {:?} {:?}",
+            self.trigger.definition(),
+            self.start,
+            self.end,
+            self.max_fires
+        )
+    }
+
+    fn triggered(&self, firetime: i64) {
+        self.fired.fetch_add(1, Ordering::SeqCst);
+        self.trigger.triggered(firetime);
+    }
+
+    fn jitter(&self, firetime: i64) -> i64 {
+        self.trigger.jitter(firetime)
+    }
+}
+
+/// How a [`JitterTrigger`] moves fire times.
+///
+/// [`JitterTrigger`]: struct.JitterTrigger.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Jitter {
+    /// Delays each fire time by up to the duration.
+    Delay(Duration),
+    /// Moves each fire time by up to the duration earlier or later.
+    Spread(Duration),
+}
+
+/// A trigger spreading the times another fires at by a pseudo-random jitter, so jobs with the
+/// same schedule aren't all due at once. The jitter of each fire time is derived from a seed,
+/// by default a hash of the trigger's id, so it's the same across restarts.
+///
+/// The scheduler runs the job when it's due, but still gives the unmoved fire time.
+///
+/// # Example
+/// ```
+/// use cron::scheduler::{CronTrigger, Jitter, JitterTrigger, Trigger};
+/// use std::time::Duration;
+///
+/// let hourly = CronTrigger::new("report".to_string(), "0 0 * * * ? *").unwrap();
+/// let trigger = JitterTrigger::new(hourly, Jitter::Delay(Duration::from_secs(60)));
+///
+/// let firetime = trigger.get_next(0).unwrap().unwrap();
+/// assert_eq!(firetime, 3_600_000);
+/// assert!((0..=60_000).contains(&trigger.jitter(firetime)));
+/// ```
+pub struct JitterTrigger<T> {
+    trigger: T,
+    jitter: Jitter,
+    seed: u64,
+}
+
+impl<T: Trigger> JitterTrigger<T> {
+    /// Wraps a trigger, seeding the jitter with its id.
+    pub fn new(trigger: T, jitter: Jitter) -> Self {
+        // FNV-1a, which unlike std's hashers is stable across builds
+        let seed = trigger
+            .get_id()
+            .bytes()
+            .fold(0xcbf2_9ce4_8422_2325, |hash: u64, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+        Self {
+            trigger,
+            jitter,
+            seed,
+        }
+    }
+
+    /// Seeds the jitter with the given value instead of the trigger's id.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Returns the wrapped trigger.
+    pub fn inner(&self) -> &T {
+        &self.trigger
+    }
+}
+
+/// Mixes the bits of a value with the SplitMix64 finalizer.
+fn splitmix64(mut x: u64) -> u64 {
+    x = x.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+impl<T: Trigger> Trigger for JitterTrigger<T> {
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        self.trigger.get_next(from)
+    }
+
+    fn get_id(&self) -> String {
+        self.trigger.get_id()
+    }
+
+    fn definition(&self) -> String {
+        format!(
+            "{} jitter {:?} {}",
+            self.trigger.definition(),
+            self.jitter,
+            self.seed
+        )
+    }
+
+    fn triggered(&self, firetime: i64) {
+        self.trigger.triggered(firetime);
+    }
+
+    fn jitter(&self, firetime: i64) -> i64 {
+        let random = splitmix64(self.seed ^ firetime as u64);
+        let (max, spread) = match self.jitter {
+            Jitter::Delay(max) => (max, false),
+            Jitter::Spread(max) => (max, true),
+        };
+        let max = i64::try_from(max.as_millis()).unwrap_or(i64::MAX / 2);
+        let jitter = if spread {
+            (random % (2 * max as u64 + 1)) as i64 - max
+        } else {
+            (random % (max as u64 + 1)) as i64
+        };
+        jitter.saturating_add(self.trigger.jitter(firetime))
+    }
+}
+
+/// Returns whether a trigger fires at the given time, which is whether its first fire time
+/// after the millisecond before is that time.
+fn fires_at(trigger: &dyn Trigger, time: i64) -> Result<bool, TriggerError> {
+    Ok(trigger.get_next(time.saturating_sub(1))? == Some(time))
+}
+
+impl Times for Arc<dyn Trigger> {
+    type Time = i64;
+    type Error = TriggerError;
+
+    fn first_from(&self, start: i64) -> Result<Option<i64>, TriggerError> {
+        self.get_next(start.saturating_sub(1))
+    }
+
+    fn first_after(&self, time: i64) -> Result<Option<i64>, TriggerError> {
+        self.get_next(time)
+    }
+
+    fn contains_time(&self, time: i64) -> Result<bool, TriggerError> {
+        fires_at(self.as_ref(), time)
+    }
+
+    fn gave_up() -> TriggerError {
+        TriggerError::new(format!(
+            "no fire time found within {} candidates",
+            MAX_STEPS
+        ))
+    }
+}
+
+fn definitions(triggers: &[Arc<dyn Trigger>]) -> String {
+    let definitions: Vec<String> = triggers.iter().map(|t| t.definition()).collect();
+    definitions.join(", ")
+}
+
+/// A trigger firing whenever any of its triggers fires, once for times several fire at.
+pub struct AnyOf {
+    id: String,
+    triggers: Vec<Arc<dyn Trigger>>,
+}
+
+impl AnyOf {
+    /// Creates a trigger firing whenever any of the given triggers fires.
+    pub fn new(id: String, triggers: Vec<Arc<dyn Trigger>>) -> Self {
+        Self { id, triggers }
+    }
+}
+
+impl Trigger for AnyOf {
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        let mut next: Option<i64> = None;
+        for trigger in &self.triggers {
+            if let Some(time) = trigger.get_next(from)? {
+                next = Some(next.map_or(time, |next| next.min(time)));
+            }
+        }
+        Ok(next)
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn definition(&self) -> String {
+        format!("any of [{}]", definitions(&self.triggers))
+    }
+
+    fn triggered(&self, firetime: i64) {
+        for trigger in &self.triggers {
+            if fires_at(trigger.as_ref(), firetime).unwrap_or(false) {
+                trigger.triggered(firetime);
+            }
         }
     }
 }
+
+/// A trigger firing only at the times all of its triggers fire at. Without triggers, it
+/// never fires.
+///
+/// Whether a trigger fires at a time is found from its first fire time after the millisecond
+/// before, so triggers whose times depend on where they're computed from, like an
+/// [`EveryTrigger`] without an anchor, should be anchored.
+///
+/// [`EveryTrigger`]: struct.EveryTrigger.html
+pub struct AllOf {
+    id: String,
+    triggers: Vec<Arc<dyn Trigger>>,
+}
+
+impl AllOf {
+    /// Creates a trigger firing only at the times all of the given triggers fire at.
+    pub fn new(id: String, triggers: Vec<Arc<dyn Trigger>>) -> Self {
+        Self { id, triggers }
+    }
+}
+
+impl Trigger for AllOf {
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        compose::first_common(&self.triggers, from.saturating_add(1))
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn definition(&self) -> String {
+        format!("all of [{}]", definitions(&self.triggers))
+    }
+
+    fn triggered(&self, firetime: i64) {
+        for trigger in &self.triggers {
+            trigger.triggered(firetime);
+        }
+    }
+}
+
+/// A trigger firing at the times of a trigger, except those another trigger fires at.
+///
+/// Whether the excluding trigger fires at a time is found like for [`AllOf`].
+///
+/// # Example
+/// ```
+/// use cron::scheduler::{CronTrigger, Except, Trigger};
+/// use std::sync::Arc;
+///
+/// // every 15 minutes, except during the 02:00 to 03:00 maintenance
+/// let every = CronTrigger::new("every".to_string(), "0 */15 * * * ? *").unwrap();
+/// let maintenance = CronTrigger::new("maintenance".to_string(), "* * 2 * * ? *").unwrap();
+/// let trigger = Except::new("id".to_string(), Arc::new(every), Arc::new(maintenance));
+///
+/// let hour = 3_600_000;
+/// assert_eq!(trigger.get_next(hour + 45 * 60_000), Ok(Some(3 * hour)));
+/// ```
+///
+/// [`AllOf`]: struct.AllOf.html
+pub struct Except {
+    id: String,
+    trigger: Arc<dyn Trigger>,
+    except: Arc<dyn Trigger>,
+}
+
+impl Except {
+    /// Creates a trigger firing at the times of `trigger`, except those `except` fires at.
+    pub fn new(id: String, trigger: Arc<dyn Trigger>, except: Arc<dyn Trigger>) -> Self {
+        Self {
+            id,
+            trigger,
+            except,
+        }
+    }
+}
+
+impl Trigger for Except {
+    fn get_next(&self, from: i64) -> Result<Option<i64>, TriggerError> {
+        compose::first_except(&self.trigger, &self.except, from.saturating_add(1))
+    }
+
+    fn get_id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn definition(&self) -> String {
+        format!(
+            "{} except {}",
+            self.trigger.definition(),
+            self.except.definition()
+        )
+    }
+
+    fn triggered(&self, firetime: i64) {
+        self.trigger.triggered(firetime);
+    }
+}
+
+/// The changes made by [`Scheduler::reload`].
+///
+/// [`Scheduler::reload`]: struct.Scheduler.html#method.reload
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ReloadReport {
+    /// The ids of the jobs that weren't registered before
+    pub added: Vec<String>,
+    /// The ids of the registered jobs that are no longer defined
+    pub removed: Vec<String>,
+    /// The ids of the jobs whose definition changed
+    pub changed: Vec<String>,
+}
+
+impl ReloadReport {
+    /// Returns whether the reload didn't change anything.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Why a job was retired. Retired jobs are no longer scheduled, and stay registered until
+/// they're taken with [`Scheduler::take_retired`].
+///
+/// [`Scheduler::take_retired`]: struct.Scheduler.html#method.take_retired
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Retired {
+    /// The job's trigger has no more fire times.
+    Completed,
+    /// The job's trigger failed to compute its next fire time.
+    Failed(TriggerError),
+}
+
+/// How a paused job is rescheduled when it's resumed with [`Scheduler::resume_job`].
+///
+/// [`Scheduler::resume_job`]: struct.Scheduler.html#method.resume_job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ResumePolicy {
+    /// Skips the fire times missed while paused and schedules the first fire time after now.
+    #[default]
+    Skip,
+    /// Fires once right away if any fire time was missed while paused, then continues with
+    /// the fire times after now.
+    FireOnce,
+    /// Fires every fire time missed while paused, starting with the one the job was queued
+    /// for when it was paused.
+    CatchUp,
+}
+
+/// What a [`Scheduler`] holds about a job, returned by [`Scheduler::jobs`]. Times are in
+/// milliseconds since the Unix epoch.
+///
+/// [`Scheduler`]: struct.Scheduler.html
+/// [`Scheduler::jobs`]: struct.Scheduler.html#method.jobs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JobInfo {
+    pub id: String,
+    /// The trigger's [`definition`]
+    ///
+    /// [`definition`]: trait.Trigger.html#method.definition
+    pub description: String,
+    /// The fire time the job will fire at next, which paused and retired jobs don't have
+    pub next_firetime: Option<i64>,
+    pub last_firetime: Option<i64>,
+    pub paused: bool,
+    /// How many times the job fired since it was added
+    pub fire_count: u64,
+    pub group: Option<String>,
+    pub tags: Vec<String>,
+    pub version: u64,
+}
+
+impl JobInfo {
+    /// Returns the job as a JSON object with the same field names.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "id": self.id,
+            "description": self.description,
+            "next_firetime": self.next_firetime,
+            "last_firetime": self.last_firetime,
+            "paused": self.paused,
+            "fire_count": self.fire_count,
+            "group": self.group,
+            "tags": self.tags,
+            "version": self.version,
+        })
+    }
+}
+
+struct Job {
+    trigger: Arc<dyn Trigger>,
+    last_firetime: Option<i64>,
+    /// The fire time the job is queued for, which is due after the trigger's jitter. Paused
+    /// jobs keep the fire time they were queued for.
+    next_firetime: Option<i64>,
+    /// Whether the job is paused, and left out of the queue
+    paused: bool,
+    group: Option<String>,
+    tags: BTreeSet<String>,
+    /// Taken from the scheduler's counter whenever the job is added or its trigger replaced
+    version: u64,
+    fire_count: u64,
+}
+
+impl Job {
+    fn new(
+        trigger: Arc<dyn Trigger>,
+        last_firetime: Option<i64>,
+        next_firetime: Option<i64>,
+        version: u64,
+    ) -> Self {
+        Self {
+            trigger,
+            last_firetime,
+            next_firetime,
+            paused: false,
+            group: None,
+            tags: BTreeSet::new(),
+            version,
+            fire_count: 0,
+        }
+    }
+
+    /// Returns the fire time the job will fire at next, which paused jobs don't have.
+    fn upcoming_firetime(&self) -> Option<i64> {
+        self.next_firetime.filter(|_| !self.paused)
+    }
+
+    fn info(&self, id: &str) -> JobInfo {
+        JobInfo {
+            id: id.to_string(),
+            description: self.trigger.definition(),
+            next_firetime: self.upcoming_firetime(),
+            last_firetime: self.last_firetime,
+            paused: self.paused,
+            fire_count: self.fire_count,
+            group: self.group.clone(),
+            tags: self.tags.iter().cloned().collect(),
+            version: self.version,
+        }
+    }
+}
+
+/// The queued jobs, by the time they're due
+type Queue = PriorityQueue<String, Reverse<i64>>;
+
+/// Queues the first fire time of a job after `from` and returns it, or retires the job if its
+/// trigger has none or fails.
+fn schedule(
+    id: &str,
+    trigger: &dyn Trigger,
+    from: i64,
+    queue: &mut Queue,
+    retired: &mut Vec<(String, Retired)>,
+) -> Option<i64> {
+    let reason = match trigger.get_next(from) {
+        Ok(Some(next_firetime)) => {
+            let due = next_firetime.saturating_add(trigger.jitter(next_firetime));
+            queue.push(id.to_string(), Reverse(due));
+            return Some(next_firetime);
+        }
+        Ok(None) => Retired::Completed,
+        Err(e) => Retired::Failed(e),
+    };
+    queue.remove(id);
+    retired.push((id.to_string(), reason));
+    None
+}
+
+#[derive(Clone)]
+pub struct Scheduler {
+    triggers: Arc<Mutex<HashMap<String, Job>>>,
+    queue: Arc<Mutex<Queue>>,
+    /// The jobs retired since the last call to [`take_retired`]
+    ///
+    /// [`take_retired`]: struct.Scheduler.html#method.take_retired
+    retired: Arc<Mutex<Vec<(String, Retired)>>>,
+    clock: Arc<dyn Clock>,
+    /// Notified whenever jobs are added, removed or rescheduled, to wake [`wait_next`]
+    ///
+    /// [`wait_next`]: struct.Scheduler.html#method.wait_next
+    changed: Arc<Notify>,
+    /// The last version given to a job, shared by all jobs so versions are never reused
+    versions: Arc<AtomicU64>,
+}
+
+impl Scheduler {
+    pub fn new() -> Self {
+        Self::with_clock(Arc::new(SystemClock))
+    }
+
+    /// Creates a scheduler reading the current time from the given clock, such as a
+    /// [`ManualClock`] to simulate time in tests.
+    ///
+    /// [`ManualClock`]: ../clock/struct.ManualClock.html
+    pub fn with_clock(clock: Arc<dyn Clock>) -> Self {
+        let queue = PriorityQueue::<String, Reverse<i64>>::new();
+        let triggers = HashMap::<String, Job>::new();
+        Self {
+            triggers: Arc::new(Mutex::new(triggers)),
+            queue: Arc::new(Mutex::new(queue)),
+            retired: Arc::new(Mutex::new(Vec::new())),
+            clock,
+            changed: Arc::new(Notify::new()),
+            versions: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Returns the clock the scheduler reads the current time from.
+    pub fn clock(&self) -> &Arc<dyn Clock> {
+        &self.clock
+    }
+
+    /// Returns the current time of the scheduler's clock, in milliseconds since the Unix epoch.
+    pub fn now(&self) -> i64 {
+        self.clock.now().timestamp_millis()
+    }
+
+    /// Adds a job scheduled from now. A job whose trigger has no fire time after now, or
+    /// fails, is retired right away.
+    ///
+    /// A job already registered with the same id is replaced by a new job, which loses its
+    /// state. Use [`try_add_job`] to keep it, or [`upsert_job`] to replace only its trigger.
+    ///
+    /// [`try_add_job`]: struct.Scheduler.html#method.try_add_job
+    /// [`upsert_job`]: struct.Scheduler.html#method.upsert_job
+    pub fn add_job(&mut self, job: Arc<dyn Trigger>) {
+        let now = self.now();
+        self.insert(job, None, now);
+    }
+
+    pub fn add_job_with_previous_time(&mut self, job: Arc<dyn Trigger>, last_firetime: i64) {
+        self.insert(job, Some(last_firetime), last_firetime);
+    }
+
+    /// Adds a job scheduled from now like [`add_job`], unless a job with the same id is
+    /// already registered.
+    ///
+    /// [`add_job`]: struct.Scheduler.html#method.add_job
+    pub fn try_add_job(&mut self, job: Arc<dyn Trigger>) -> Result<(), SchedulerError> {
+        let now = self.now();
+        let id = job.get_id();
+        let mut triggers = self.triggers.lock().unwrap();
+        if triggers.contains_key(&id) {
+            return Err(SchedulerError::DuplicateId(id));
+        }
+        self.insert_into(&mut triggers, id, job, None, now);
+        Ok(())
+    }
+
+    fn insert(&mut self, trigger: Arc<dyn Trigger>, last_firetime: Option<i64>, from: i64) {
+        let id = trigger.get_id();
+        let mut triggers = self.triggers.lock().unwrap();
+        self.insert_into(&mut triggers, id, trigger, last_firetime, from);
+    }
+
+    /// Schedules a new job and registers it in the locked jobs, replacing any job with the
+    /// same id.
+    fn insert_into(
+        &self,
+        triggers: &mut HashMap<String, Job>,
+        id: String,
+        trigger: Arc<dyn Trigger>,
+        last_firetime: Option<i64>,
+        from: i64,
+    ) {
+        let mut queue = self.queue.lock().unwrap();
+        let mut retired = self.retired.lock().unwrap();
+        let next_firetime = schedule(&id, trigger.as_ref(), from, &mut queue, &mut retired);
+        let job = Job::new(trigger, last_firetime, next_firetime, self.next_version());
+        triggers.insert(id, job);
+        self.changed.notify_waiters();
+    }
+
+    /// Returns a version no job of the scheduler had before.
+    fn next_version(&self) -> u64 {
+        self.versions.fetch_add(1, Ordering::SeqCst) + 1
+    }
+
+    pub fn remove_job(&mut self, id: String) {
+        let mut trigger = self.triggers.lock().unwrap();
+        let mut queue = self.queue.lock().unwrap();
+
+        queue.remove(&id);
+        trigger.remove(&id);
+        self.changed.notify_waiters();
+    }
+
+    /// Pauses a job, which stays registered but isn't fired until it's resumed. Returns
+    /// whether the job was paused, which it isn't if it isn't registered or already paused.
+    pub fn pause_job(&mut self, id: &str) -> bool {
+        self.pause_jobs([id]) == 1
+    }
+
+    /// Pauses the jobs with the given ids like [`pause_job`], and returns how many were
+    /// paused.
+    ///
+    /// [`pause_job`]: struct.Scheduler.html#method.pause_job
+    pub fn pause_jobs<'a, I>(&mut self, ids: I) -> usize
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let mut triggers = self.triggers.lock().unwrap();
+        let mut queue = self.queue.lock().unwrap();
+
+        let mut paused = 0;
+        for id in ids {
+            match triggers.get_mut(id) {
+                Some(job) if !job.paused => {
+                    job.paused = true;
+                    queue.remove(id);
+                    paused += 1;
+                }
+                _ => {}
+            }
+        }
+        if paused > 0 {
+            self.changed.notify_waiters();
+        }
+        paused
+    }
+
+    /// Resumes a paused job, rescheduling it according to the given policy. Returns whether
+    /// the job was resumed, which it isn't if it isn't registered or isn't paused. A retired
+    /// job stays retired when resumed.
+    pub fn resume_job(&mut self, id: &str, policy: ResumePolicy) -> bool {
+        self.resume_jobs([id], policy) == 1
+    }
+
+    /// Resumes the jobs with the given ids like [`resume_job`], and returns how many were
+    /// resumed.
+    ///
+    /// [`resume_job`]: struct.Scheduler.html#method.resume_job
+    pub fn resume_jobs<'a, I>(&mut self, ids: I, policy: ResumePolicy) -> usize
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let now = self.now();
+        let mut triggers = self.triggers.lock().unwrap();
+        let mut queue = self.queue.lock().unwrap();
+        let mut retired = self.retired.lock().unwrap();
+
+        let mut resumed = 0;
+        for id in ids {
+            let job = match triggers.get_mut(id) {
+                Some(job) if job.paused => job,
+                _ => continue,
+            };
+            job.paused = false;
+            resumed += 1;
+
+            // retired jobs have no fire time to resume from
+            let pending = match job.next_firetime {
+                Some(pending) => pending,
+                None => continue,
+            };
+            let trigger = job.trigger.as_ref();
+            job.next_firetime = match policy {
+                ResumePolicy::FireOnce if pending <= now => {
+                    queue.push(id.to_string(), Reverse(now));
+                    Some(now)
+                }
+                ResumePolicy::Skip if pending <= now => {
+                    schedule(id, trigger, now, &mut queue, &mut retired)
+                }
+                _ => {
+                    let due = pending.saturating_add(trigger.jitter(pending));
+                    queue.push(id.to_string(), Reverse(due));
+                    Some(pending)
+                }
+            };
+        }
+        if resumed > 0 {
+            self.changed.notify_waiters();
+        }
+        resumed
+    }
+
+    /// Returns whether the job with the given id is registered and paused.
+    pub fn is_paused(&self, id: &str) -> bool {
+        let triggers = self.triggers.lock().unwrap();
+        triggers.get(id).is_some_and(|job| job.paused)
+    }
+
+    /// Replaces the trigger of a registered job, which stays registered under the given id.
+    /// The job is rescheduled from its last fire time, or from now if it never fired, and
+    /// stays paused if it was. Returns whether the job was registered.
+    pub fn reschedule_job(&mut self, id: &str, trigger: Arc<dyn Trigger>) -> bool {
+        self.replace(id, trigger, None, false).is_ok()
+    }
+
+    /// Replaces the trigger of the job with the same id like [`reschedule_job`], keeping its
+    /// last fire time and other state, or adds the job like [`add_job`] if none is registered.
+    /// Returns the job's new version.
+    ///
+    /// [`reschedule_job`]: struct.Scheduler.html#method.reschedule_job
+    /// [`add_job`]: struct.Scheduler.html#method.add_job
+    pub fn upsert_job(&mut self, job: Arc<dyn Trigger>) -> u64 {
+        let id = job.get_id();
+        self.replace(&id, job, None, true)
+            .expect("upserts without a version always succeed")
+    }
+
+    /// Replaces the trigger of the job with the same id like [`upsert_job`] if the job is
+    /// still at the given version, and returns its new version. An update made against a
+    /// version that has since changed is rejected, so concurrent updates don't overwrite each
+    /// other unnoticed.
+    ///
+    /// [`upsert_job`]: struct.Scheduler.html#method.upsert_job
+    pub fn update_job(
+        &mut self,
+        job: Arc<dyn Trigger>,
+        version: u64,
+    ) -> Result<u64, SchedulerError> {
+        let id = job.get_id();
+        self.replace(&id, job, Some(version), false)
+    }
+
+    /// Returns the version of a registered job. A job gets a new version whenever it's added
+    /// or its trigger is replaced. Versions go up and are never reused, even by a job added
+    /// again with the id of a removed one.
+    pub fn job_version(&self, id: &str) -> Option<u64> {
+        let triggers = self.triggers.lock().unwrap();
+        triggers.get(id).map(|job| job.version)
+    }
+
+    /// Replaces the trigger of a job if it's at the expected version, adding the job if it's
+    /// not registered and `add` is set.
+    fn replace(
+        &mut self,
+        id: &str,
+        trigger: Arc<dyn Trigger>,
+        expected: Option<u64>,
+        add: bool,
+    ) -> Result<u64, SchedulerError> {
+        let now = self.now();
+        let mut triggers = self.triggers.lock().unwrap();
+        let mut queue = self.queue.lock().unwrap();
+        let mut retired = self.retired.lock().unwrap();
+
+        let job = match triggers.get_mut(id) {
+            Some(job) => job,
+            None if add => {
+                let next = schedule(id, trigger.as_ref(), now, &mut queue, &mut retired);
+                let version = self.next_version();
+                triggers.insert(id.to_string(), Job::new(trigger, None, next, version));
+                self.changed.notify_waiters();
+                return Ok(version);
+            }
+            None => return Err(SchedulerError::UnknownId(id.to_string())),
+        };
+        match expected {
+            Some(expected) if expected != job.version => {
+                return Err(SchedulerError::StaleVersion {
+                    id: id.to_string(),
+                    expected,
+                    current: job.version,
+                });
+            }
+            _ => {}
+        }
+
+        let from = job.last_firetime.unwrap_or(now);
+        job.next_firetime = schedule(id, trigger.as_ref(), from, &mut queue, &mut retired);
+        job.trigger = trigger;
+        job.version = self.next_version();
+        if job.paused {
+            queue.remove(id);
+        }
+        self.changed.notify_waiters();
+        Ok(job.version)
+    }
+
+    /// Puts a registered job in a group, or takes it out of its group with `None`. A job is
+    /// in at most one group, and keeps it when its trigger is rescheduled or reloaded.
+    /// Returns whether the job was registered.
+    pub fn set_group(&mut self, id: &str, group: Option<String>) -> bool {
+        let mut triggers = self.triggers.lock().unwrap();
+        match triggers.get_mut(id) {
+            Some(job) => {
+                job.group = group;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Tags a registered job. A job can have any number of tags, and keeps them when its
+    /// trigger is rescheduled or reloaded. Returns whether the job was registered.
+    pub fn tag_job(&mut self, id: &str, tag: impl Into<String>) -> bool {
+        let mut triggers = self.triggers.lock().unwrap();
+        match triggers.get_mut(id) {
+            Some(job) => {
+                job.tags.insert(tag.into());
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Removes a tag from a job, and returns whether the job had it.
+    pub fn untag_job(&mut self, id: &str, tag: &str) -> bool {
+        let mut triggers = self.triggers.lock().unwrap();
+        triggers.get_mut(id).is_some_and(|job| job.tags.remove(tag))
+    }
+
+    /// Returns the ids of the jobs in the given group with their next fire time, sorted by
+    /// id. Paused and retired jobs have no next fire time.
+    pub fn jobs_in_group(&self, group: &str) -> Vec<(String, Option<i64>)> {
+        self.find_jobs(|job| job.group.as_deref() == Some(group))
+    }
+
+    /// Returns the ids of the jobs with the given tag with their next fire time, sorted by id.
+    /// Paused and retired jobs have no next fire time.
+    pub fn jobs_tagged(&self, tag: &str) -> Vec<(String, Option<i64>)> {
+        self.find_jobs(|job| job.tags.contains(tag))
+    }
+
+    fn find_jobs(&self, filter: impl Fn(&Job) -> bool) -> Vec<(String, Option<i64>)> {
+        let triggers = self.triggers.lock().unwrap();
+        let mut jobs: Vec<_> = triggers
+            .iter()
+            .filter(|(_, job)| filter(job))
+            .map(|(id, job)| (id.clone(), job.upcoming_firetime()))
+            .collect();
+        jobs.sort();
+        jobs
+    }
+
+    fn group_ids(&self, group: &str) -> Vec<String> {
+        let jobs = self.jobs_in_group(group);
+        jobs.into_iter().map(|(id, _)| id).collect()
+    }
+
+    /// Pauses the jobs in the given group like [`pause_job`], and returns how many were paused.
+    ///
+    /// [`pause_job`]: struct.Scheduler.html#method.pause_job
+    pub fn pause_group(&mut self, group: &str) -> usize {
+        let ids = self.group_ids(group);
+        self.pause_jobs(ids.iter().map(String::as_str))
+    }
+
+    /// Resumes the paused jobs in the given group like [`resume_job`], and returns how many
+    /// were resumed.
+    ///
+    /// [`resume_job`]: struct.Scheduler.html#method.resume_job
+    pub fn resume_group(&mut self, group: &str, policy: ResumePolicy) -> usize {
+        let ids = self.group_ids(group);
+        self.resume_jobs(ids.iter().map(String::as_str), policy)
+    }
+
+    /// Removes the jobs in the given group, and returns how many were removed.
+    pub fn remove_group(&mut self, group: &str) -> usize {
+        let mut triggers = self.triggers.lock().unwrap();
+        let mut queue = self.queue.lock().unwrap();
+
+        let before = triggers.len();
+        triggers.retain(|id, job| {
+            let keep = job.group.as_deref() != Some(group);
+            if !keep {
+                queue.remove(id);
+            }
+            keep
+        });
+        let removed = before - triggers.len();
+        if removed > 0 {
+            self.changed.notify_waiters();
+        }
+        removed
+    }
+
+    /// Replaces the registered jobs with the given ones. New jobs are added and jobs that are
+    /// no longer given are removed. Jobs whose [`definition`] changed are rescheduled from
+    /// now, or from their last fire time if it's later, so the runs of the new schedule that
+    /// were missed before the reload aren't all due at once. Unchanged jobs keep their
+    /// registered trigger, state and next fire time, and stay retired if they were.
+    ///
+    /// [`definition`]: trait.Trigger.html#method.definition
+    pub fn reload<I>(&mut self, jobs: I) -> ReloadReport
+    where
+        I: IntoIterator<Item = Arc<dyn Trigger>>,
+    {
+        let now = self.now();
+        let mut report = ReloadReport::default();
+        let mut triggers = self.triggers.lock().unwrap();
+        let mut queue = self.queue.lock().unwrap();
+        let mut retired = self.retired.lock().unwrap();
+
+        let mut seen = HashMap::new();
+        for trigger in jobs {
+            let id = trigger.get_id();
+            let job = match triggers.get(&id) {
+                Some(job) if job.trigger.definition() == trigger.definition() => {
+                    // keep the registered trigger, which may hold state like a fire count
+                    Job {
+                        trigger: job.trigger.clone(),
+                        group: job.group.clone(),
+                        tags: job.tags.clone(),
+                        ..*job
+                    }
+                }
+                Some(job) => {
+                    report.changed.push(id.clone());
+                    let from = job.last_firetime.map_or(now, |last| last.max(now));
+                    let next = schedule(&id, trigger.as_ref(), from, &mut queue, &mut retired);
+                    if job.paused {
+                        queue.remove(&id);
+                    }
+                    Job {
+                        trigger,
+                        next_firetime: next,
+                        version: self.next_version(),
+                        group: job.group.clone(),
+                        tags: job.tags.clone(),
+                        ..*job
+                    }
+                }
+                None => {
+                    report.added.push(id.clone());
+                    let next = schedule(&id, trigger.as_ref(), now, &mut queue, &mut retired);
+                    Job::new(trigger, None, next, self.next_version())
+                }
+            };
+            seen.insert(id, job);
+        }
+
+        for id in triggers.keys() {
+            if !seen.contains_key(id) {
+                queue.remove(id);
+                report.removed.push(id.clone());
+            }
+        }
+        report.removed.sort();
+        *triggers = seen;
+
+        if !report.is_empty() {
+            self.changed.notify_waiters();
+        }
+        report
+    }
+
+    /// Returns the fire time of the job due soonest and schedules that job's following fire
+    /// time. Use [`next_firing`] to also get the job's id.
+    ///
+    /// [`next_firing`]: struct.Scheduler.html#method.next_firing
+    pub fn get_next_firetime(&mut self) -> Option<i64> {
+        self.next_firing().map(|(_, firetime)| firetime)
+    }
+
+    /// Returns the id and fire time of the job due soonest and schedules that job's following
+    /// fire time, retiring the job if there's none. Fire times are in milliseconds since the
+    /// Unix epoch.
+    pub fn next_firing(&mut self) -> Option<(String, i64)> {
+        self.pop_firing(i64::MAX)
+    }
+
+    /// Pops the job due soonest if it's due by the given time and schedules its following
+    /// fire time.
+    fn pop_firing(&mut self, due_by: i64) -> Option<(String, i64)> {
+        let mut triggers = self.triggers.lock().unwrap();
+        let mut queue = self.queue.lock().unwrap();
+        let mut retired = self.retired.lock().unwrap();
+
+        if queue.peek()?.1 .0 > due_by {
+            return None;
+        }
+        let (job_id, Reverse(due)) = queue.pop()?;
+        let job = triggers.get_mut(&job_id)?;
+        let firetime = job.next_firetime.unwrap_or(due);
+        job.last_firetime = Some(firetime);
+        job.fire_count += 1;
+        job.trigger.triggered(firetime);
+        job.next_firetime = schedule(
+            &job_id,
+            job.trigger.as_ref(),
+            firetime,
+            &mut queue,
+            &mut retired,
+        );
+        Some((job_id, firetime))
+    }
+
+    /// Returns the jobs retired since the last call, with why they were retired, in the
+    /// order they were retired. The jobs are removed, unless they were scheduled again since.
+    pub fn take_retired(&mut self) -> Vec<(String, Retired)> {
+        let mut triggers = self.triggers.lock().unwrap();
+        let taken = core::mem::take(&mut *self.retired.lock().unwrap());
+        for (id, _) in &taken {
+            if triggers
+                .get(id)
+                .is_some_and(|job| job.next_firetime.is_none())
+            {
+                triggers.remove(id);
+            }
+        }
+        taken
+    }
+
+    /// Returns the id and fire time of the job due soonest without scheduling anything.
+    pub fn peek_next_firing(&self) -> Option<(String, i64)> {
+        let triggers = self.triggers.lock().unwrap();
+        let queue = self.queue.lock().unwrap();
+        queue.peek().map(|(job_id, Reverse(due))| {
+            let firetime = triggers.get(job_id).and_then(|job| job.next_firetime);
+            (job_id.clone(), firetime.unwrap_or(*due))
+        })
+    }
+
+    /// Returns what the scheduler holds about each registered job, sorted by id.
+    pub fn jobs(&self) -> Vec<JobInfo> {
+        let triggers = self.triggers.lock().unwrap();
+        let mut jobs: Vec<_> = triggers.iter().map(|(id, job)| job.info(id)).collect();
+        jobs.sort_by(|a, b| a.id.cmp(&b.id));
+        jobs
+    }
+
+    /// Returns the next `n` fire times across all jobs with their job's id, in the order of
+    /// their fire times, without scheduling anything.
+    ///
+    /// The times are computed without the triggers firing, so a trigger that changes when
+    /// it fires, like a [`BoundedTrigger`] limiting how many times it fires, may fire
+    /// differently. Fire times a trigger fails to compute end its job's times. The jobs are
+    /// only locked while their triggers are collected, so computing the times doesn't hold
+    /// up firing, but it takes as many trigger calls as times returned: jobs that never
+    /// stop firing give `n` times however large it is.
+    ///
+    /// [`BoundedTrigger`]: struct.BoundedTrigger.html
+    pub fn next_n_firings(&self, n: usize) -> Vec<(String, i64)> {
+        let jobs: Vec<_> = {
+            let triggers = self.triggers.lock().unwrap();
+            triggers
+                .iter()
+                .filter_map(|(id, job)| {
+                    let next = job.upcoming_firetime()?;
+                    Some((next, id.clone(), job.trigger.clone()))
+                })
+                .collect()
+        };
+        let mut upcoming: BinaryHeap<_> = jobs
+            .iter()
+            .enumerate()
+            .map(|(index, (next, id, _))| Reverse((*next, id.as_str(), index)))
+            .collect();
+
+        let mut firings = Vec::with_capacity(n.min(jobs.len()));
+        while firings.len() < n {
+            let Reverse((firetime, id, index)) = match upcoming.pop() {
+                Some(next) => next,
+                None => break,
+            };
+            if let Ok(Some(next)) = jobs[index].2.get_next(firetime) {
+                upcoming.push(Reverse((next, id, index)));
+            }
+            firings.push((id.to_string(), firetime));
+        }
+        firings
+    }
+
+    /// Returns a JSON snapshot of the scheduler, with the current time, the jobs as returned
+    /// by [`jobs`] and the ids of the jobs retired but not yet taken with [`take_retired`].
+    ///
+    /// [`jobs`]: struct.Scheduler.html#method.jobs
+    /// [`take_retired`]: struct.Scheduler.html#method.take_retired
+    pub fn snapshot(&self) -> Value {
+        let jobs: Vec<_> = self.jobs().iter().map(JobInfo::to_json).collect();
+        let retired: Vec<_> = {
+            let retired = self.retired.lock().unwrap();
+            retired.iter().map(|(id, _)| id.clone()).collect()
+        };
+        json!({
+            "now": self.now(),
+            "jobs": jobs,
+            "retired": retired,
+        })
+    }
+
+    /// Returns when the job due soonest is due, after its trigger's jitter.
+    fn peek_due(&self) -> Option<i64> {
+        let queue = self.queue.lock().unwrap();
+        queue.peek().map(|(_, Reverse(due))| *due)
+    }
+
+    /// Waits on the scheduler's clock until the job due soonest is due, then returns its id
+    /// and fire time and schedules its following fire time like [`next_firing`].
+    ///
+    /// Adding, removing or rescheduling jobs, including through clones of the scheduler,
+    /// wakes the wait so a job added with an earlier fire time isn't fired late. Without jobs,
+    /// this waits until one is added.
+    ///
+    /// [`next_firing`]: struct.Scheduler.html#method.next_firing
+    pub async fn wait_next(&mut self) -> (String, i64) {
+        let changed = self.changed.clone();
+        let clock = self.clock.clone();
+        loop {
+            // listen before peeking so a change in between isn't missed
+            let notified = changed.notified();
+            let due = match self.peek_due() {
+                Some(due) => due,
+                None => {
+                    notified.await;
+                    continue;
+                }
+            };
+
+            if let Some(firing) = self.pop_firing(self.now()) {
+                return firing;
+            }
+            match DateTime::from_timestamp_millis(due) {
+                Some(time) => {
+                    tokio::select! {
+                        _ = clock.sleep_until(time) => {}
+                        _ = notified => {}
+                    }
+                }
+                None => notified.await,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::clock::ManualClock;
+    use chrono::TimeZone;
+
+    fn format_millis(millis: i64) -> String {
+        let time = DateTime::from_timestamp_millis(millis).unwrap();
+        time.format("%a %Y-%m-%d %H:%M").to_string()
+    }
+
+    #[test]
+    fn next_firing_follows_fire_time_order() {
+        let mut scheduler = Scheduler::new();
+        let slow = EveryTrigger::new(Duration::from_millis(10), "slow".to_string());
+        let fast = EveryTrigger::new(Duration::from_millis(3), "fast".to_string());
+        scheduler.add_job_with_previous_time(Arc::new(slow), 0);
+        scheduler.add_job_with_previous_time(Arc::new(fast), 0);
+
+        assert_eq!(scheduler.peek_next_firing(), Some(("fast".to_string(), 3)));
+        let firings: Vec<_> = (0..5).filter_map(|_| scheduler.next_firing()).collect();
+        assert_eq!(
+            firings,
+            [
+                ("fast".to_string(), 3),
+                ("fast".to_string(), 6),
+                ("fast".to_string(), 9),
+                ("slow".to_string(), 10),
+                ("fast".to_string(), 12),
+            ]
+        );
+    }
+
+    #[test]
+    fn reload_keeps_state_of_unchanged_jobs() {
+        let every = |id: &str, millis| -> Arc<dyn Trigger> {
+            Arc::new(EveryTrigger::new(
+                Duration::from_millis(millis),
+                id.to_string(),
+            ))
+        };
+
+        let clock = ManualClock::new(DateTime::from_timestamp_millis(0).unwrap());
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock));
+        scheduler.add_job_with_previous_time(every("a", 10), 0);
+        scheduler.add_job_with_previous_time(every("b", 20), 0);
+        scheduler.add_job_with_previous_time(every("c", 30), 0);
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 10)));
+
+        let report = scheduler.reload([every("a", 10), every("b", 5), every("d", 40)]);
+        assert_eq!(report.added, ["d"]);
+        assert_eq!(report.removed, ["c"]);
+        assert_eq!(report.changed, ["b"]);
+
+        // b is rescheduled from its last fire time, a continues where it was
+        assert_eq!(scheduler.next_firing(), Some(("b".to_string(), 5)));
+        assert_eq!(scheduler.next_firing(), Some(("b".to_string(), 10)));
+        assert_eq!(scheduler.next_firing(), Some(("b".to_string(), 15)));
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 20)));
+
+        assert!(scheduler
+            .reload([every("a", 10), every("b", 5), every("d", 40)])
+            .is_empty());
+    }
+
+    #[test]
+    fn reload_skips_runs_missed_by_a_new_schedule() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 3, 0, 0).unwrap();
+        let clock = ManualClock::new(start);
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock.clone()));
+        let job = |expr: &str| -> Arc<dyn Trigger> {
+            Arc::new(CronTrigger::new("job".to_string(), expr).unwrap())
+        };
+        scheduler.add_job_with_previous_time(job("0 0 3 * * ? *"), start.timestamp_millis() - 1);
+        assert_eq!(
+            scheduler.next_firing().map(|(_, time)| time),
+            Some(start.timestamp_millis())
+        );
+
+        // a day later the job becomes hourly
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 3, 30, 0).unwrap();
+        clock.set(now);
+        assert_eq!(scheduler.reload([job("0 0 * * * ? *")]).changed, ["job"]);
+
+        let (_, next) = scheduler.next_firing().unwrap();
+        assert!(next > now.timestamp_millis());
+        assert_eq!(format_millis(next), "Tue 2024-01-02 04:00");
+    }
+
+    #[test]
+    fn paused_jobs_resume_by_policy() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = ManualClock::new(start);
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock.clone()));
+        let hourly = |id: &str| CronTrigger::new(id.to_string(), "0 0 * * * ? *").unwrap();
+        for id in ["skip", "once", "catch-up"] {
+            scheduler.add_job(Arc::new(hourly(id)));
+        }
+
+        assert_eq!(
+            scheduler.pause_jobs(["skip", "once", "catch-up", "unknown"]),
+            3
+        );
+        assert!(scheduler.is_paused("once"));
+        assert!(!scheduler.pause_job("once"));
+        assert_eq!(scheduler.next_firing(), None);
+
+        // three hours pass while paused
+        clock.set(Utc.with_ymd_and_hms(2024, 1, 1, 3, 30, 0).unwrap());
+        assert!(scheduler.resume_job("skip", ResumePolicy::Skip));
+        assert!(scheduler.resume_job("once", ResumePolicy::FireOnce));
+        assert!(scheduler.resume_job("catch-up", ResumePolicy::CatchUp));
+        assert!(!scheduler.resume_job("skip", ResumePolicy::Skip));
+
+        let mut firings: Vec<_> = (0..7)
+            .filter_map(|_| scheduler.next_firing())
+            .map(|(id, firetime)| format!("{} {}", id, format_millis(firetime)))
+            .collect();
+        // the jobs due at the same time fire in any order
+        firings[4..].sort();
+        assert_eq!(
+            firings,
+            [
+                "catch-up Mon 2024-01-01 01:00",
+                "catch-up Mon 2024-01-01 02:00",
+                "catch-up Mon 2024-01-01 03:00",
+                "once Mon 2024-01-01 03:30",
+                "catch-up Mon 2024-01-01 04:00",
+                "once Mon 2024-01-01 04:00",
+                "skip Mon 2024-01-01 04:00",
+            ]
+        );
+    }
+
+    #[test]
+    fn rescheduled_jobs_keep_their_state() {
+        let mut scheduler = Scheduler::new();
+        let every = |millis| Arc::new(EveryTrigger::new(Duration::from_millis(millis), "a".into()));
+        scheduler.add_job_with_previous_time(every(10), 0);
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 10)));
+
+        // rescheduled from the last fire time
+        assert!(scheduler.reschedule_job("a", every(3)));
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 13)));
+
+        scheduler.pause_job("a");
+        assert!(scheduler.reschedule_job("a", every(5)));
+        assert!(scheduler.is_paused("a"));
+        assert_eq!(scheduler.next_firing(), None);
+        scheduler.resume_job("a", ResumePolicy::CatchUp);
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 18)));
+
+        assert!(!scheduler.reschedule_job("b", every(5)));
+    }
+
+    #[test]
+    fn groups_and_tags_manage_jobs_together() {
+        let clock = ManualClock::new(DateTime::from_timestamp_millis(0).unwrap());
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock));
+        let every = |id: &str, millis| -> Arc<dyn Trigger> {
+            Arc::new(EveryTrigger::new(
+                Duration::from_millis(millis),
+                id.to_string(),
+            ))
+        };
+        for (id, millis) in [("a", 10), ("b", 20), ("c", 30)] {
+            scheduler.add_job_with_previous_time(every(id, millis), 0);
+        }
+        assert!(scheduler.set_group("a", Some("tenant".to_string())));
+        assert!(scheduler.set_group("b", Some("tenant".to_string())));
+        assert!(!scheduler.set_group("d", Some("tenant".to_string())));
+        assert!(scheduler.tag_job("b", "billing"));
+        assert!(scheduler.tag_job("c", "billing"));
+
+        assert_eq!(
+            scheduler.jobs_in_group("tenant"),
+            [("a".to_string(), Some(10)), ("b".to_string(), Some(20))]
+        );
+        assert_eq!(scheduler.pause_group("tenant"), 2);
+        assert_eq!(
+            scheduler.jobs_tagged("billing"),
+            [("b".to_string(), None), ("c".to_string(), Some(30))]
+        );
+        assert_eq!(scheduler.next_firing(), Some(("c".to_string(), 30)));
+
+        // groups and tags survive reloads
+        scheduler.reload([every("a", 10), every("b", 5), every("c", 30)]);
+        assert_eq!(scheduler.resume_group("tenant", ResumePolicy::CatchUp), 2);
+        assert_eq!(
+            scheduler.jobs_tagged("billing"),
+            [("b".to_string(), Some(5)), ("c".to_string(), Some(60))]
+        );
+
+        assert!(scheduler.untag_job("c", "billing"));
+        assert!(!scheduler.untag_job("c", "billing"));
+        assert_eq!(scheduler.remove_group("tenant"), 2);
+        assert_eq!(scheduler.jobs_in_group("tenant"), []);
+        assert_eq!(scheduler.next_firing(), Some(("c".to_string(), 60)));
+    }
+
+    #[test]
+    fn versions_reject_stale_updates() {
+        let mut scheduler = Scheduler::new();
+        let every = |millis| Arc::new(EveryTrigger::new(Duration::from_millis(millis), "a".into()));
+        scheduler.add_job_with_previous_time(every(10), 0);
+        assert_eq!(
+            scheduler.try_add_job(every(20)),
+            Err(SchedulerError::DuplicateId("a".to_string()))
+        );
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 10)));
+        assert_eq!(scheduler.job_version("a"), Some(1));
+
+        // upserts keep the last fire time
+        assert_eq!(scheduler.upsert_job(every(3)), 2);
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 13)));
+
+        assert_eq!(scheduler.update_job(every(5), 2), Ok(3));
+        assert_eq!(
+            scheduler.update_job(every(7), 2),
+            Err(SchedulerError::StaleVersion {
+                id: "a".to_string(),
+                expected: 2,
+                current: 3,
+            })
+        );
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 18)));
+
+        // replacing the whole job still moves its version on
+        scheduler.add_job_with_previous_time(every(10), 0);
+        assert_eq!(scheduler.job_version("a"), Some(4));
+        assert_eq!(
+            scheduler.upsert_job(Arc::new(AtTrigger::new("b".into(), Utc::now()))),
+            5
+        );
+        assert_eq!(
+            scheduler.update_job(Arc::new(AtTrigger::new("c".into(), Utc::now())), 1),
+            Err(SchedulerError::UnknownId("c".to_string()))
+        );
+
+        // a job added again after being removed doesn't reuse the removed job's versions
+        scheduler.remove_job("a".to_string());
+        scheduler.add_job_with_previous_time(every(10), 0);
+        assert_eq!(scheduler.job_version("a"), Some(6));
+        assert!(scheduler.update_job(every(20), 4).is_err());
+    }
+
+    #[test]
+    fn jobs_and_upcoming_firings() {
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock));
+        let every =
+            |id: &str, secs| Arc::new(EveryTrigger::new(Duration::from_secs(secs), id.to_string()));
+        scheduler.add_job(every("a", 20));
+        scheduler.add_job(every("b", 30));
+        scheduler.tag_job("b", "billing");
+
+        let firings: Vec<_> = scheduler
+            .next_n_firings(5)
+            .into_iter()
+            .map(|(id, firetime)| format!("{} {}", id, firetime / 1000 % 3600))
+            .collect();
+        assert_eq!(firings, ["a 20", "b 30", "a 40", "a 60", "b 60"]);
+        // nothing was scheduled
+        assert_eq!(scheduler.next_firing().unwrap().0, "a");
+        scheduler.pause_job("b");
+
+        let jobs = scheduler.jobs();
+        assert_eq!(jobs.len(), 2);
+        let a = &jobs[0];
+        assert_eq!((a.id.as_str(), a.fire_count), ("a", 1));
+        assert_eq!(a.last_firetime.map(|last| last / 1000 % 3600), Some(20));
+        assert_eq!(a.next_firetime.map(|next| next / 1000 % 3600), Some(40));
+        let b = &jobs[1];
+        assert!(b.paused);
+        assert_eq!((b.next_firetime, b.fire_count), (None, 0));
+        assert_eq!(b.tags, ["billing"]);
+        assert_eq!(scheduler.next_n_firings(2).len(), 2);
+
+        let snapshot = scheduler.snapshot();
+        assert_eq!(snapshot["jobs"][1]["id"], "b");
+        assert_eq!(snapshot["jobs"][1]["paused"], true);
+        assert_eq!(snapshot["jobs"][0]["description"], jobs[0].description);
+        assert_eq!(snapshot["now"], scheduler.now());
+
+        // asking for all the times of jobs that stop firing
+        let once = Utc.with_ymd_and_hms(2024, 1, 1, 1, 0, 0).unwrap();
+        scheduler.upsert_job(Arc::new(DatesTrigger::new("a".to_string(), [once])));
+        assert_eq!(
+            scheduler.next_n_firings(usize::MAX),
+            [("a".to_string(), once.timestamp_millis())]
+        );
+    }
+
+    #[test]
+    fn reload_keeps_registered_triggers() {
+        let limited = || -> Arc<dyn Trigger> {
+            let every = EveryTrigger::new(Duration::from_millis(10), "a".to_string());
+            Arc::new(BoundedTrigger::new(every).with_max_fires(2))
+        };
+        let mut scheduler = Scheduler::new();
+        scheduler.add_job_with_previous_time(limited(), 0);
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 10)));
+
+        // the fresh trigger's fire count doesn't replace the registered one's
+        assert!(scheduler.reload([limited()]).is_empty());
+        assert_eq!(scheduler.next_firing(), Some(("a".to_string(), 20)));
+        assert_eq!(scheduler.next_firing(), None);
+
+        let cron = CronTrigger::new("b".to_string(), "0 0 12 * * ? *").unwrap();
+        assert_eq!(cron.definition(), "cron 0 0 12 * * ? *");
+    }
+
+    #[test]
+    fn firings_follow_a_manual_clock() {
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock.clone()));
+        let weekdays = CronTrigger::new("weekdays".to_string(), "0 30 9 ? * MON-FRI *").unwrap();
+        let every = EveryTrigger::new(Duration::from_secs(36 * 3600), "every".to_string());
+        scheduler.add_job(Arc::new(weekdays));
+        scheduler.add_job(Arc::new(every));
+
+        let firings: Vec<_> = (0..8)
+            .filter_map(|_| scheduler.next_firing())
+            .map(|(id, firetime)| format!("{} {}", id, format_millis(firetime)))
+            .collect();
+        assert_eq!(
+            firings,
+            [
+                "weekdays Mon 2024-01-01 09:30",
+                "weekdays Tue 2024-01-02 09:30",
+                "every Tue 2024-01-02 12:00",
+                "weekdays Wed 2024-01-03 09:30",
+                "every Thu 2024-01-04 00:00",
+                "weekdays Thu 2024-01-04 09:30",
+                "weekdays Fri 2024-01-05 09:30",
+                "every Fri 2024-01-05 12:00",
+            ]
+        );
+
+        // jobs added later are scheduled from the clock's time, skipping the weekend
+        clock.advance(chrono::Duration::days(5));
+        let weekdays = CronTrigger::new("late".to_string(), "0 30 9 ? * MON-FRI *").unwrap();
+        scheduler.add_job(Arc::new(weekdays));
+        scheduler.remove_job("weekdays".to_string());
+        scheduler.remove_job("every".to_string());
+        let (id, firetime) = scheduler.next_firing().unwrap();
+        assert_eq!(
+            (id.as_str(), format_millis(firetime).as_str()),
+            ("late", "Mon 2024-01-08 09:30")
+        );
+    }
+
+    #[tokio::test]
+    async fn adding_an_earlier_job_wakes_wait_next() {
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let clock = ManualClock::new(start);
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock.clone()));
+        let hourly = EveryTrigger::new(Duration::from_secs(3600), "hourly".to_string());
+        scheduler.add_job(Arc::new(hourly));
+
+        let mut waiter = scheduler.clone();
+        let wait = tokio::spawn(async move { waiter.wait_next().await });
+        tokio::task::yield_now().await;
+
+        let minutely = EveryTrigger::new(Duration::from_secs(60), "minutely".to_string());
+        scheduler.add_job(Arc::new(minutely));
+        clock.advance(chrono::Duration::minutes(1));
+
+        // without the wake, the wait would sleep until the hourly job
+        let (id, firetime) = tokio::time::timeout(Duration::from_secs(5), wait)
+            .await
+            .expect("the wait wasn't woken")
+            .unwrap();
+        assert_eq!(id, "minutely");
+        assert_eq!(format_millis(firetime), "Mon 2024-01-01 00:01");
+    }
+
+    struct FailingTrigger;
+
+    impl Trigger for FailingTrigger {
+        fn get_next(&self, _from: i64) -> Result<Option<i64>, TriggerError> {
+            Err(TriggerError::new("broken"))
+        }
+
+        fn get_id(&self) -> String {
+            "failing".to_string()
+        }
+
+        fn definition(&self) -> String {
+            "failing".to_string()
+        }
+    }
+
+    #[test]
+    fn finished_and_failing_jobs_are_retired() {
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2023, 6, 1, 0, 0, 0).unwrap());
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock));
+        let yearly = CronTrigger::new("yearly".to_string(), "0 0 0 1 1 ? 2024-2025").unwrap();
+        scheduler.add_job(Arc::new(yearly));
+        scheduler.add_job(Arc::new(FailingTrigger));
+        assert_eq!(
+            scheduler.take_retired(),
+            [(
+                "failing".to_string(),
+                Retired::Failed(TriggerError::new("broken"))
+            )]
+        );
+
+        let firings: Vec<_> = (0..3)
+            .filter_map(|_| scheduler.next_firing())
+            .map(|(id, firetime)| format!("{} {}", id, format_millis(firetime)))
+            .collect();
+        assert_eq!(
+            firings,
+            ["yearly Mon 2024-01-01 00:00", "yearly Wed 2025-01-01 00:00"]
+        );
+        assert_eq!(scheduler.jobs().len(), 1);
+        assert_eq!(
+            scheduler.take_retired(),
+            [("yearly".to_string(), Retired::Completed)]
+        );
+        assert!(scheduler.take_retired().is_empty());
+        assert!(scheduler.jobs().is_empty());
+
+        // out of range times fail instead of panicking
+        let cron = CronTrigger::new("late".to_string(), "* * * * * ? *").unwrap();
+        scheduler.add_job_with_previous_time(Arc::new(cron), i64::MAX);
+        let retired = scheduler.take_retired();
+        assert!(matches!(&retired[..], [(id, Retired::Failed(_))] if id == "late"));
+        assert_eq!(scheduler.peek_next_firing(), None);
+    }
+
+    #[test]
+    fn one_off_triggers_fire_at_their_dates() {
+        let at = |day, hour| Utc.with_ymd_and_hms(2024, 1, day, hour, 0, 0).unwrap();
+        let clock = ManualClock::new(at(2, 0));
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock));
+        scheduler.add_job(Arc::new(AtTrigger::new("once".to_string(), at(3, 12))));
+        scheduler.add_job(Arc::new(AtTrigger::new("past".to_string(), at(1, 12))));
+        let dates = [at(4, 9), at(1, 9), at(2, 9), at(4, 9)];
+        scheduler.add_job(Arc::new(DatesTrigger::new("dates".to_string(), dates)));
+        assert_eq!(
+            scheduler.take_retired(),
+            [("past".to_string(), Retired::Completed)]
+        );
+
+        let firings: Vec<_> = (0..5)
+            .filter_map(|_| scheduler.next_firing())
+            .map(|(id, firetime)| format!("{} {}", id, format_millis(firetime)))
+            .collect();
+        assert_eq!(
+            firings,
+            [
+                "dates Tue 2024-01-02 09:00",
+                "once Wed 2024-01-03 12:00",
+                "dates Thu 2024-01-04 09:00",
+            ]
+        );
+        assert_eq!(
+            scheduler.take_retired(),
+            [
+                ("once".to_string(), Retired::Completed),
+                ("dates".to_string(), Retired::Completed)
+            ]
+        );
+        // fired one-off jobs don't pile up
+        assert!(scheduler.jobs().is_empty());
+    }
+
+    #[test]
+    fn bounded_triggers_stop_at_their_bounds() {
+        let at = |hour| Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap();
+        let clock = ManualClock::new(at(0));
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock));
+        let hourly = |id: &str| EveryTrigger::new(Duration::from_secs(3600), id.to_string());
+
+        let window = BoundedTrigger::new(hourly("window").with_anchor(at(0)))
+            .with_start(at(3))
+            .with_end(at(5));
+        let limited = Arc::new(BoundedTrigger::new(hourly("limited")).with_max_fires(2));
+        scheduler.add_job(Arc::new(window));
+        scheduler.add_job(limited.clone());
+        assert_eq!(limited.remaining(), Some(2));
+
+        let firings: Vec<_> = (0..6)
+            .filter_map(|_| scheduler.next_firing())
+            .map(|(id, firetime)| format!("{} {}", id, format_millis(firetime)))
+            .collect();
+        assert_eq!(
+            firings,
+            [
+                "limited Mon 2024-01-01 01:00",
+                "limited Mon 2024-01-01 02:00",
+                "window Mon 2024-01-01 03:00",
+                "window Mon 2024-01-01 04:00",
+                "window Mon 2024-01-01 05:00",
+            ]
+        );
+        assert_eq!(limited.remaining(), Some(0));
+        assert_eq!(
+            scheduler.take_retired(),
+            [
+                ("limited".to_string(), Retired::Completed),
+                ("window".to_string(), Retired::Completed)
+            ]
+        );
+    }
+
+    #[test]
+    fn bounded_triggers_jump_to_a_far_start() {
+        let start = Utc.with_ymd_and_hms(2030, 6, 1, 0, 0, 0).unwrap();
+        let every_milli = EveryTrigger::new(Duration::from_millis(1), "every".to_string())
+            .with_anchor(DateTime::UNIX_EPOCH);
+        let trigger = BoundedTrigger::new(every_milli).with_start(start);
+        assert_eq!(trigger.get_next(0), Ok(Some(start.timestamp_millis())));
+    }
+
+    #[test]
+    fn anchored_every_triggers_keep_their_phase() {
+        let at = |hour, minute| Utc.with_ymd_and_hms(2024, 1, 1, hour, minute, 0).unwrap();
+        let every = || {
+            EveryTrigger::new(Duration::from_secs(90 * 60), "every".to_string())
+                .with_anchor(at(0, 0))
+                .with_end(at(6, 0))
+        };
+        let firings = |scheduler: &mut Scheduler| -> Vec<String> {
+            (0..5)
+                .filter_map(|_| scheduler.next_firing())
+                .map(|(_, firetime)| format_millis(firetime))
+                .collect()
+        };
+
+        // adding the job at different times, or restarting it from its last fire time, keeps
+        // the same fire times
+        let expected = [
+            "Mon 2024-01-01 03:00",
+            "Mon 2024-01-01 04:30",
+            "Mon 2024-01-01 06:00",
+        ];
+        for now in [at(2, 0), at(2, 59)] {
+            let mut scheduler = Scheduler::with_clock(Arc::new(ManualClock::new(now)));
+            scheduler.add_job(Arc::new(every()));
+            assert_eq!(firings(&mut scheduler), expected);
+        }
+        let mut scheduler = Scheduler::new();
+        scheduler.add_job_with_previous_time(Arc::new(every()), at(1, 30).timestamp_millis());
+        assert_eq!(firings(&mut scheduler), expected);
+
+        // a start without an anchor is the first fire time
+        let clock = ManualClock::new(at(0, 0));
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock));
+        let every = EveryTrigger::new(Duration::from_secs(90 * 60), "every".to_string())
+            .with_start(at(1, 10));
+        scheduler.add_job(Arc::new(every));
+        assert_eq!(
+            &firings(&mut scheduler)[..2],
+            ["Mon 2024-01-01 01:10", "Mon 2024-01-01 02:40"]
+        );
+    }
+
+    #[test]
+    fn calendar_intervals_handle_short_months() {
+        let anchor = Utc.with_ymd_and_hms(2023, 1, 31, 9, 0, 0).unwrap();
+        let times = |trigger: CalendarIntervalTrigger| -> Vec<String> {
+            let mut from = anchor.timestamp_millis() - 1;
+            let mut times = Vec::new();
+            while let Ok(Some(next)) = trigger.get_next(from) {
+                if times.len() == 5 {
+                    break;
+                }
+                times.push(format_millis(next));
+                from = next;
+            }
+            times
+        };
+        let monthly = |every, overflow| {
+            CalendarIntervalTrigger::new("id".to_string(), anchor, every, CalendarUnit::Months)
+                .with_overflow(overflow)
+        };
+
+        assert_eq!(
+            times(monthly(3, DayOverflow::Clamp)),
+            [
+                "Tue 2023-01-31 09:00",
+                "Sun 2023-04-30 09:00",
+                "Mon 2023-07-31 09:00",
+                "Tue 2023-10-31 09:00",
+                "Wed 2024-01-31 09:00",
+            ]
+        );
+        assert_eq!(
+            times(monthly(1, DayOverflow::Skip)),
+            [
+                "Tue 2023-01-31 09:00",
+                "Fri 2023-03-31 09:00",
+                "Wed 2023-05-31 09:00",
+                "Mon 2023-07-31 09:00",
+                "Thu 2023-08-31 09:00",
+            ]
+        );
+        assert_eq!(
+            &times(monthly(1, DayOverflow::RollOver))[..3],
+            [
+                "Tue 2023-01-31 09:00",
+                "Fri 2023-03-03 09:00",
+                "Fri 2023-03-31 09:00",
+            ]
+        );
+
+        let leap_day = Utc.with_ymd_and_hms(2024, 2, 29, 0, 0, 0).unwrap();
+        let yearly =
+            CalendarIntervalTrigger::new("id".to_string(), leap_day, 1, CalendarUnit::Years)
+                .with_overflow(DayOverflow::Skip);
+        let next = yearly.get_next(leap_day.timestamp_millis()).unwrap();
+        assert_eq!(
+            next.map(format_millis).as_deref(),
+            Some("Tue 2028-02-29 00:00")
+        );
+    }
+
+    #[test]
+    fn calendar_intervals_keep_the_local_time() {
+        let offset = chrono::FixedOffset::east_opt(2 * 3600).unwrap();
+        let anchor = offset.with_ymd_and_hms(2024, 3, 30, 9, 0, 0).unwrap();
+        let trigger = CalendarIntervalTrigger::new("id".to_string(), anchor, 2, CalendarUnit::Days);
+        let from = Utc.with_ymd_and_hms(2024, 4, 1, 12, 0, 0).unwrap();
+        let next = trigger.get_next(from.timestamp_millis()).unwrap();
+        assert_eq!(
+            next.map(format_millis).as_deref(),
+            Some("Wed 2024-04-03 07:00")
+        );
+
+        let weekly = CalendarIntervalTrigger::new("id".to_string(), anchor, 1, CalendarUnit::Weeks);
+        let next = weekly.get_next(from.timestamp_millis()).unwrap();
+        assert_eq!(
+            next.map(format_millis).as_deref(),
+            Some("Sat 2024-04-06 07:00")
+        );
+    }
+
+    #[test]
+    fn daily_time_intervals_restart_each_window() {
+        let trigger = DailyTimeIntervalTrigger::new(
+            "id".to_string(),
+            Duration::from_secs(7 * 60),
+            NaiveTime::from_hms_opt(8, 30, 0).unwrap(),
+            NaiveTime::from_hms_opt(17, 45, 0).unwrap(),
+        )
+        .with_days([Weekday::Fri, Weekday::Mon]);
+        let next = |from: DateTime<Utc>| {
+            let next = trigger.get_next(from.timestamp_millis()).unwrap();
+            next.map(format_millis).unwrap()
+        };
+
+        // Wednesday is skipped, and steps cross the hour without restarting
+        let wednesday = Utc.with_ymd_and_hms(2024, 1, 3, 12, 0, 0).unwrap();
+        assert_eq!(next(wednesday), "Fri 2024-01-05 08:30");
+        let friday = Utc.with_ymd_and_hms(2024, 1, 5, 8, 55, 0).unwrap();
+        assert_eq!(next(friday), "Fri 2024-01-05 08:58");
+        let friday = Utc.with_ymd_and_hms(2024, 1, 5, 8, 58, 0).unwrap();
+        assert_eq!(next(friday), "Fri 2024-01-05 09:05");
+        let friday = Utc.with_ymd_and_hms(2024, 1, 5, 17, 40, 0).unwrap();
+        assert_eq!(next(friday), "Fri 2024-01-05 17:43");
+        let friday = Utc.with_ymd_and_hms(2024, 1, 5, 17, 43, 0).unwrap();
+        assert_eq!(next(friday), "Mon 2024-01-08 08:30");
+
+        // windows follow the time zone's days and times
+        let offset = chrono::FixedOffset::west_opt(5 * 3600).unwrap();
+        let trigger = trigger.with_timezone(offset);
+        let next = trigger.get_next(wednesday.timestamp_millis()).unwrap();
+        assert_eq!(
+            next.map(format_millis).as_deref(),
+            Some("Fri 2024-01-05 13:30")
+        );
+    }
+
+    #[test]
+    fn jitter_spreads_due_times_but_keeps_fire_times() {
+        let clock = ManualClock::new(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        let mut scheduler = Scheduler::with_clock(Arc::new(clock));
+        let hourly = |id: &str| {
+            let cron = CronTrigger::new(id.to_string(), "0 0 * * * ? *").unwrap();
+            JitterTrigger::new(cron, Jitter::Spread(Duration::from_secs(60)))
+        };
+        for id in ["a", "b", "c"] {
+            scheduler.add_job(Arc::new(hourly(id)));
+        }
+
+        let one = Utc
+            .with_ymd_and_hms(2024, 1, 1, 1, 0, 0)
+            .unwrap()
+            .timestamp_millis();
+        let mut jitters: Vec<_> = ["a", "b", "c"]
+            .iter()
+            .map(|id| (hourly(id).jitter(one), id.to_string()))
+            .collect();
+        jitters.sort();
+        assert!(jitters.iter().all(|(jitter, _)| jitter.abs() <= 60_000));
+        assert_ne!(jitters[0].0, jitters[2].0);
+
+        // jobs are popped in the order they're due, with their unmoved fire time
+        let firings: Vec<_> = (0..3).filter_map(|_| scheduler.next_firing()).collect();
+        let expected: Vec<_> = jitters.into_iter().map(|(_, id)| (id, one)).collect();
+        assert_eq!(firings, expected);
+
+        // the jitter differs between fire times and depends on the seed
+        let trigger = hourly("a");
+        assert_ne!(trigger.jitter(one), trigger.jitter(one + 3_600_000));
+        assert_ne!(trigger.jitter(one), hourly("a").with_seed(7).jitter(one));
+    }
+
+    #[test]
+    fn combined_triggers() {
+        let cron = |expr: &str| -> Arc<dyn Trigger> {
+            Arc::new(CronTrigger::new(expr.to_string(), expr).unwrap())
+        };
+        let times = |trigger: &dyn Trigger, n| -> Vec<String> {
+            let mut from = Utc
+                .with_ymd_and_hms(2024, 1, 1, 0, 0, 0)
+                .unwrap()
+                .timestamp_millis();
+            let mut times = Vec::new();
+            while let Ok(Some(next)) = trigger.get_next(from) {
+                if times.len() == n {
+                    break;
+                }
+                times.push(format_millis(next));
+                from = next;
+            }
+            times
+        };
+
+        let any = AnyOf::new(
+            "any".to_string(),
+            vec![cron("0 0 9 ? * MON-FRI *"), cron("0 0 9,11 ? * MON *")],
+        );
+        assert_eq!(
+            times(&any, 3),
+            [
+                "Mon 2024-01-01 09:00",
+                "Mon 2024-01-01 11:00",
+                "Tue 2024-01-02 09:00"
+            ]
+        );
+
+        let hour = Duration::from_secs(3600);
+        let anchored = EveryTrigger::new(hour * 5, "every".to_string())
+            .with_anchor(Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap());
+        let all = AllOf::new(
+            "all".to_string(),
+            vec![Arc::new(anchored), cron("0 0 */2 * * ? *")],
+        );
+        assert_eq!(
+            times(&all, 2),
+            ["Mon 2024-01-01 10:00", "Mon 2024-01-01 20:00"]
+        );
+        let never = AllOf::new(
+            "never".to_string(),
+            vec![cron("0 0 1 * * ? *"), cron("0 0 2 * * ? *")],
+        );
+        assert!(never.get_next(0).is_err());
+
+        let except = Except::new(
+            "except".to_string(),
+            cron("0 */30 * * * ? *"),
+            cron("* * 1-2 * * ? *"),
+        );
+        assert_eq!(
+            times(&except, 3),
+            [
+                "Mon 2024-01-01 00:30",
+                "Mon 2024-01-01 03:00",
+                "Mon 2024-01-01 03:30"
+            ]
+        );
+    }
+}